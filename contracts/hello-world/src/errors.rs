@@ -44,4 +44,166 @@ pub enum TokenError {
     /// Nombre o símbolo inválido (vacío o muy largo)
     /// Validación de metadatos en initialize()
     InvalidMetadata = 9,
+    
+    /// Expiración de allowance inválida
+    /// El ledger de expiración ya pasó y el amount es mayor a 0
+    InvalidExpiration = 10,
+    
+    /// Payload de datos demasiado grande
+    /// Se lanza si `data` supera MAX_DATA_LENGTH bytes
+    DataTooLarge = 11,
+    
+    /// El owner alcanzó el máximo de allowances distintos
+    /// Ver MaxAllowancesPerOwner (0 = ilimitado)
+    TooManyAllowances = 12,
+    
+    /// Falló el cobro del fee en XLM al sender
+    /// La llamada al SAC nativo devolvió error (ej: sin fondos)
+    FeePaymentFailed = 13,
+    
+    /// Configuración inválida
+    /// Ej: fee en basis points mayor a 10000
+    InvalidConfig = 14,
+    
+    /// La cuenta está congelada
+    /// No puede enviar ni quemar tokens hasta que se descongele
+    AccountFrozen = 15,
+    
+    /// El batch supera el tamaño máximo permitido
+    /// Evita agotar el presupuesto de recursos de Soroban
+    BatchTooLarge = 16,
+    
+    /// La operación está bloqueada por la pausa
+    /// Según la política de pausa vigente (pause_policy)
+    Paused = 17,
+    
+    /// El contrato receptor rechazó la notificación
+    /// Su hook tokens_received falló o no existe
+    NotificationFailed = 18,
+    
+    /// El snapshot consultado no existe
+    /// Debe estar entre 1 y current_snapshot_id()
+    InvalidSnapshot = 19,
+    
+    /// El spender no aceptó gastar allowances de esta cuenta
+    /// Requerido cuando RequireSpenderOptIn está activo
+    SpenderNotOptedIn = 20,
+    
+    /// La transferencia dejaría al sender con menos del mínimo
+    /// Debe conservar min_account_balance o vaciar la cuenta
+    BelowMinimum = 21,
+    
+    /// La cuenta recibió tokens hace muy poco para quemar, o la
+    /// configuración cambió hace menos de config_change_cooldown ledgers
+    CooldownActive = 22,
+    
+    /// El allowance resultante supera el tope fijado por el owner
+    /// Ver set_allowance_cap (sin tope por defecto)
+    AllowanceCapExceeded = 23,
+    
+    /// El caller no tiene el rol requerido
+    /// Ej: pause() llamado por alguien que no es admin ni guardian
+    Unauthorized = 24,
+    
+    /// El ref_id de pago no es válido
+    /// No existe (refund) o ya está en uso (transfer_from_tracked)
+    InvalidPaymentRef = 25,
+    
+    /// El pago ya fue reembolsado
+    /// Cada ref_id se puede reembolsar una sola vez
+    AlreadyRefunded = 26,
+    
+    /// Se alcanzó el máximo de holders
+    /// Ver max_holders (0 = ilimitado)
+    HolderCapReached = 27,
+    
+    /// La cuenta está protegida contra burns
+    /// Ej: la reserva o el treasury (ver set_protected)
+    ProtectedAccount = 28,
+    
+    /// El monto no alcanza a cubrir el fee fijo, o tras el fee
+    /// el receptor no recibiría nada (ver set_flat_fee)
+    AmountTooSmall = 29,
+    
+    /// El mint superaría el supply máximo
+    /// Ver set_max_supply (sin tope por defecto)
+    SupplyCapExceeded = 30,
+    
+    /// Todavía no pasó una ventana completa de inflación
+    /// Ver mint_inflation
+    InflationNotDue = 31,
+    
+    /// El destino no fue aprobado por el owner
+    /// Ver set_restrict_destinations / approve_destination
+    DestinationNotApproved = 32,
+    
+    /// La transferencia supera el volumen saliente de la ventana
+    /// Ver set_volume_limit / volume_remaining
+    VolumeLimitExceeded = 33,
+    
+    /// drain_reserve no se habilitó en initialize o el contrato no está pausado
+    /// Ver InitOptions::drain_enabled
+    DrainUnavailable = 34,
+    
+    /// El burn dejaría el token sin holders (supply en 0)
+    /// Ver set_require_non_empty
+    CannotRemoveLastHolder = 35,
+    
+    /// La configuración se bloqueó para siempre con lock_config
+    /// Los setters fallan; las operaciones siguen funcionando
+    ConfigLocked = 36,
+    
+    /// No existe una transferencia pendiente con ese id
+    /// Ya se liquidó, se canceló o nunca se creó
+    UnknownPendingTransfer = 37,
+    
+    /// La transferencia pendiente está fuera de la ventana de la operación
+    /// settle_transfer antes de settle_ledger, o cancel_transfer desde ese ledger
+    OutsideSettlementWindow = 38,
+    
+    /// El firmante ya aprobó esta propuesta
+    /// Cada firmante cuenta una sola vez para el threshold
+    AlreadyApproved = 39,
+    
+    /// No existe una propuesta pendiente con ese id
+    /// Ya se ejecutó o nunca se creó
+    UnknownProposal = 40,
+    
+    /// El oráculo de precio no cumple la condición configurada
+    /// También si el oráculo falla o no responde un i128
+    ConditionNotMet = 41,
+    
+    /// El monto no es un múltiplo de 10^decimals
+    /// Solo con set_whole_units_only activo
+    FractionalAmount = 42,
+}
+
+/// Categorías de error devueltas por error_category()
+/// 
+/// Permiten a clientes y monitoreo ramificar por tipo de error sin
+/// conocer cada código. 0 indica un código desconocido
+pub const ERROR_CATEGORY_UNKNOWN: u32 = 0;
+pub const ERROR_CATEGORY_AUTH: u32 = 1;
+pub const ERROR_CATEGORY_VALIDATION: u32 = 2;
+pub const ERROR_CATEGORY_STATE: u32 = 3;
+pub const ERROR_CATEGORY_ARITHMETIC: u32 = 4;
+
+/// Agrupa un código de TokenError en su categoría
+/// 
+/// - Auth: el caller o la cuenta no tienen permiso
+/// - Validation: los parámetros no son válidos para la operación
+/// - State: el estado del contrato impide la operación por ahora
+/// - Arithmetic: overflow en un cálculo
+pub fn error_category(code: u32) -> u32 {
+    match code {
+        15 | 20 | 24 | 28 | 32 => ERROR_CATEGORY_AUTH,
+        2 | 3 | 4 | 6 | 8 | 9 | 10 | 11 | 14 | 16 | 19 | 21 | 23 | 25 | 29 | 37 | 40 | 42 => {
+            ERROR_CATEGORY_VALIDATION
+        }
+        1 | 5 | 12 | 13 | 17 | 18 | 22 | 26 | 27 | 30 | 31 | 33 | 34 | 35 | 36 | 38 | 39 | 41 => {
+            ERROR_CATEGORY_STATE
+        }
+        7 => ERROR_CATEGORY_ARITHMETIC,
+        _ => ERROR_CATEGORY_UNKNOWN,
+    }
 }
\ No newline at end of file
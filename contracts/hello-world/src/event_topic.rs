@@ -0,0 +1,195 @@
+// src/event_topic.rs
+//! Nombres de los eventos que emite el contrato (primer topic)
+//! 
+//! Todos salen de acá para no repetir abreviaturas a mano en cada
+//! método. symbol_short! corre en tiempo de compilación: un nombre de
+//! más de 9 caracteres (o con caracteres inválidos) no compila.
+//! Las operaciones de nombre largo usan una abreviatura fija
+//! (ej: transfer_from -> trnsf_frm)
+use soroban_sdk::{symbol_short, Symbol};
+
+/// initialize()
+pub const INIT: Symbol = symbol_short!("init");
+
+/// lock_config()
+pub const LOCK_CONFIG: Symbol = symbol_short!("cfg_lock");
+
+/// set_metadata()
+pub const METADATA: Symbol = symbol_short!("metadata");
+
+/// Setters de configuración (el segundo topic es el nombre del parámetro)
+pub const CONFIG: Symbol = symbol_short!("config");
+
+/// mint(), mint_by() y demás minteos
+pub const MINT: Symbol = symbol_short!("mint");
+
+/// distribute()
+pub const DISTRIBUTE: Symbol = symbol_short!("distrib");
+
+/// mint_inflation()
+pub const INFLATION: Symbol = symbol_short!("inflation");
+
+/// claim_rewards()
+pub const REWARD: Symbol = symbol_short!("reward");
+
+/// migrate_from()
+pub const MIGRATE_FROM: Symbol = symbol_short!("migr_from");
+
+/// migrate_decimals()
+pub const MIGRATE_DECIMALS: Symbol = symbol_short!("migrate");
+
+/// burn()
+pub const BURN: Symbol = symbol_short!("burn");
+
+/// burn_proportional()
+pub const BURN_PROPORTIONAL: Symbol = symbol_short!("burn_prop");
+
+/// burn_redeem()
+pub const REDEEM: Symbol = symbol_short!("redeem");
+
+/// buyback_burn()
+pub const BUYBACK: Symbol = symbol_short!("buyback");
+
+/// clawback_batch()
+pub const CLAWBACK: Symbol = symbol_short!("clawback");
+
+/// drain_reserve()
+pub const DRAIN: Symbol = symbol_short!("drain");
+
+/// transfer()
+pub const TRANSFER: Symbol = symbol_short!("transfer");
+
+/// transfer_from()
+pub const TRANSFER_FROM: Symbol = symbol_short!("trnsf_frm");
+
+/// Fee de transferencia acreditado a cada receptor
+pub const FEE: Symbol = symbol_short!("fee");
+
+/// transfer() por encima de DelayThreshold
+pub const PENDING: Symbol = symbol_short!("pending");
+
+/// settle_transfer()
+pub const SETTLE: Symbol = symbol_short!("settle");
+
+/// cancel_transfer()
+pub const CANCEL: Symbol = symbol_short!("cancel");
+
+/// swap()
+pub const SWAP: Symbol = symbol_short!("swap");
+
+/// transfer_from_tracked()
+pub const PAYMENT_REF: Symbol = symbol_short!("pay_ref");
+
+/// refund()
+pub const REFUND: Symbol = symbol_short!("refund");
+
+/// approve()
+pub const APPROVE: Symbol = symbol_short!("approve");
+
+/// approve_with_data()
+pub const APPROVE_WITH_DATA: Symbol = symbol_short!("appr_data");
+
+/// approve_once()
+pub const APPROVE_ONCE: Symbol = symbol_short!("appr_once");
+
+/// increase_allowance()
+pub const INCREASE_ALLOWANCE: Symbol = symbol_short!("incr_allw");
+
+/// set_allowance_cap()
+pub const ALLOWANCE_CAP: Symbol = symbol_short!("allow_cap");
+
+/// set_allowance_auto_extend()
+pub const AUTO_EXTEND: Symbol = symbol_short!("auto_ext");
+
+/// revoke_allowances()
+pub const REVOKE: Symbol = symbol_short!("revoke");
+
+/// accept_spending()
+pub const SPENDER_OPT_IN: Symbol = symbol_short!("opt_in");
+
+/// open_pool()
+pub const POOL_OPEN: Symbol = symbol_short!("pool_open");
+
+/// pull()
+pub const POOL_PULL: Symbol = symbol_short!("pool_pull");
+
+/// set_restrict_destinations()
+pub const RESTRICT_DESTINATIONS: Symbol = symbol_short!("dest_lock");
+
+/// approve_destination() / revoke_destination()
+pub const DESTINATION: Symbol = symbol_short!("dest");
+
+/// set_volume_limit()
+pub const VOLUME_LIMIT: Symbol = symbol_short!("vol_limit");
+
+/// lock()
+pub const LOCK: Symbol = symbol_short!("lock");
+
+/// unlock()
+pub const UNLOCK: Symbol = symbol_short!("unlock");
+
+/// mint_vested()
+pub const VEST: Symbol = symbol_short!("vest");
+
+/// claim()
+pub const CLAIM: Symbol = symbol_short!("claim");
+
+/// emergency_unlock()
+pub const EARLY_UNLOCK: Symbol = symbol_short!("early_unl");
+
+/// freeze()
+pub const FREEZE: Symbol = symbol_short!("freeze");
+
+/// unfreeze()
+pub const UNFREEZE: Symbol = symbol_short!("unfreeze");
+
+/// set_protected()
+pub const PROTECT: Symbol = symbol_short!("protect");
+
+/// propose()
+pub const PROPOSE: Symbol = symbol_short!("propose");
+
+/// approve_proposal()
+pub const APPROVE_PROPOSAL: Symbol = symbol_short!("prop_appr");
+
+/// Propuesta ejecutada al alcanzar el threshold
+pub const EXECUTE_PROPOSAL: Symbol = symbol_short!("prop_exec");
+
+/// pause()
+pub const PAUSE: Symbol = symbol_short!("pause");
+
+/// unpause()
+pub const UNPAUSE: Symbol = symbol_short!("unpause");
+
+/// grant_role()
+pub const GRANT_ROLE: Symbol = symbol_short!("grant");
+
+/// revoke_role()
+pub const REVOKE_ROLE: Symbol = symbol_short!("revoke_rl");
+
+/// set_launch_router()
+pub const LAUNCH_ROUTER: Symbol = symbol_short!("lnch_rtr");
+
+/// snapshot()
+pub const SNAPSHOT: Symbol = symbol_short!("snapshot");
+
+/// Aviso de balance cerca de expirar
+pub const NEAR_EXPIRY: Symbol = symbol_short!("near_exp");
+
+/// Operación fallida (con EmitFailureEvents)
+pub const FAILURE: Symbol = symbol_short!("fail");
+
+/// Todos los topics, para auditar el set completo de eventos
+/// 
+/// Solo lo usan los tests (ver test_event_topics_are_unique_and_audited)
+#[cfg(test)]
+pub const ALL: [Symbol; 57] = [
+    INIT, LOCK_CONFIG, METADATA, CONFIG, MINT, DISTRIBUTE, INFLATION, REWARD, MIGRATE_FROM, MIGRATE_DECIMALS,
+    BURN, BURN_PROPORTIONAL, REDEEM, BUYBACK, CLAWBACK, DRAIN, TRANSFER, TRANSFER_FROM, FEE, PENDING,
+    SETTLE, CANCEL, SWAP, PAYMENT_REF, REFUND, APPROVE, APPROVE_WITH_DATA, APPROVE_ONCE, INCREASE_ALLOWANCE,
+    ALLOWANCE_CAP, AUTO_EXTEND, REVOKE, SPENDER_OPT_IN, POOL_OPEN, POOL_PULL, RESTRICT_DESTINATIONS,
+    DESTINATION, VOLUME_LIMIT,
+    LOCK, UNLOCK, VEST, CLAIM, EARLY_UNLOCK, FREEZE, UNFREEZE, PROTECT, PROPOSE, APPROVE_PROPOSAL,
+    EXECUTE_PROPOSAL, PAUSE, UNPAUSE, GRANT_ROLE, REVOKE_ROLE, LAUNCH_ROUTER, SNAPSHOT, NEAR_EXPIRY,
+    FAILURE,
+];
@@ -2,33 +2,178 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, Address, Env, String, 
-    symbol_short, Symbol
+    contract, contractimpl, events::Topics, panic_with_error, token, Address, Bytes, BytesN, 
+    Env, IntoVal, String, Symbol, Val, Vec
 };
 
 mod storage;
 mod errors;
+mod event_topic;
+mod test;
 
-use storage::{DataKey, TokenMetadata};
-use errors::TokenError;
+use storage::{
+    AccountStorage, AdminAction, AllowanceValue, BalanceProof, DataKey, DataKeyExt, DataKeyExt2,
+    FeePayer, HealthReport, InflationConfig, InitOptions, LastCounterparty, MigrationStatus,
+    Multisig, PaymentRecord, PendingTransfer, Permissions, PriceCondition, PriceDirection,
+    Proposal, Role, RoundingMode, SpendingPool, TokenConfig, TokenMetadata, TransferReceipt,
+    TtlTiers, VestTranche
+};
+use errors::{error_category, TokenError};
+
+/// Versión del código del contrato (reportada por health())
+pub const CONTRACT_VERSION: u32 = 1;
 
 /// Constantes de configuración
 const MAX_DECIMALS: u32 = 18;
 const MAX_NAME_LENGTH: u32 = 100;
 const MAX_SYMBOL_LENGTH: u32 = 32;
+const MAX_DATA_LENGTH: u32 = 256;
+
+/// Ledger de expiración usado por approve() (allowance sin vencimiento)
+const NO_EXPIRATION: u32 = u32::MAX;
+
+/// Máximo de checkpoints de supply retenidos (se descartan los más viejos)
+const MAX_SUPPLY_CHECKPOINTS: u32 = 500;
+
+/// Denominador de los basis points (10000 = 100%)
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Máximo de elementos por llamada en cualquier método batch
+/// 
+/// Todos los batch lo aplican vía validate_batch_len() para no
+/// agotar el presupuesto de recursos de Soroban
+const MAX_BATCH_SIZE: u32 = 100;
+
+/// Máximo de receptores entre los que se reparte el fee
+const MAX_FEE_RECIPIENTS: u32 = 10;
+
+/// Máximo de cuentas por rol en initialize_with_roles()
+const MAX_INITIAL_ROLE_MEMBERS: u32 = 10;
+
+/// Ledgers que se recuerda un op_id de transfer_idempotent (~1 día)
+const OP_ID_TTL: u32 = 17_280;
+
+/// Largo por defecto de la ventana de volumen saliente (~1 día)
+const VOLUME_WINDOW_LEDGERS: u32 = 17_280;
+
+/// Ledgers a los que se extiende el TTL de un balance sin TtlTiers
+const BALANCE_TTL_LEDGERS: u32 = 200_000;
+
+/// TTL restante (en ledgers) por debajo del cual se avisa near_exp (~1 día)
+const NEAR_EXPIRY_LEDGERS: u32 = 17_280;
+
+/// Escala del reward rate: rate / REWARD_RATE_SCALE por unidad de
+/// balance y por ledger (p. ej. ~5% anual ≈ 7_900)
+const REWARD_RATE_SCALE: i128 = 1_000_000_000_000;
+
+/// Bits de la política de pausa (operaciones bloqueadas mientras está pausado)
+pub const PAUSE_TRANSFER: u32 = 1 << 0;
+pub const PAUSE_TRANSFER_FROM: u32 = 1 << 1;
+pub const PAUSE_MINT: u32 = 1 << 2;
+pub const PAUSE_BURN: u32 = 1 << 3;
+pub const PAUSE_APPROVE: u32 = 1 << 4;
+
+/// Política de pausa por defecto: bloquea todas las operaciones
+pub const PAUSE_ALL: u32 = PAUSE_TRANSFER
+    | PAUSE_TRANSFER_FROM
+    | PAUSE_MINT
+    | PAUSE_BURN
+    | PAUSE_APPROVE;
+
+/// Interfaz estándar de tokens fungibles de Soroban (SEP-41)
+/// 
+/// Subconjunto portable: wallets, DEXs y demás integraciones pueden
+/// depender solo de estos métodos y llamar al contrato con cualquier
+/// cliente SEP-41
+pub trait StandardToken {
+    /// Consulta el allowance entre dos cuentas
+    /// 
+    /// Devuelve cuánto puede gastar `spender` de los tokens de `from`
+    fn allowance(env: Env, from: Address, spender: Address) -> i128;
+    
+    /// Aprueba a otro usuario para gastar tokens
+    /// 
+    /// Permite que `spender` gaste hasta `amount` tokens de la cuenta
+    /// de `from` hasta `expiration_ledger` (u32::MAX = sin vencimiento).
+    /// Se puede revocar con amount=0; con amount > 0 una expiración
+    /// pasada falla con InvalidExpiration
+    fn approve(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128,
+        expiration_ledger: u32
+    ) -> Result<(), TokenError>;
+    
+    /// Consulta el balance de una cuenta
+    /// 
+    /// Devuelve 0 si la cuenta nunca ha recibido tokens
+    fn balance(env: Env, account: Address) -> i128;
+    
+    /// Transfiere tokens entre cuentas
+    /// 
+    /// Requiere autorización de `from`
+    /// No permite transferencias a sí mismo. Por encima de
+    /// DelayThreshold queda pendiente (ver set_transfer_delay)
+    fn transfer(
+        env: Env, 
+        from: Address, 
+        to: Address, 
+        amount: i128
+    ) -> Result<(), TokenError>;
+    
+    /// Transfiere tokens en nombre de otro usuario
+    /// 
+    /// Requiere allowance previo mediante approve()
+    /// Reduce el allowance automáticamente. Por encima de
+    /// DelayThreshold queda pendiente (el allowance se consume igual)
+    fn transfer_from(
+        env: Env, 
+        spender: Address, 
+        from: Address, 
+        to: Address, 
+        amount: i128
+    ) -> Result<(), TokenError>;
+    
+    /// Destruye tokens reduciendo el supply
+    /// 
+    /// Reduce el supply total y el balance del owner
+    /// Requiere autorización del owner
+    fn burn(env: Env, from: Address, amount: i128) -> Result<(), TokenError>;
+    
+    /// Destruye tokens de otro usuario usando su allowance
+    /// 
+    /// Requiere autorización de `spender` y reduce el allowance
+    /// como transfer_from()
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) -> Result<(), TokenError>;
+    
+    /// Decimales del token (0 antes de initialize)
+    fn decimals(env: Env) -> u32;
+    
+    /// Nombre del token (vacío antes de initialize)
+    fn name(env: Env) -> String;
+    
+    /// Símbolo del token (vacío antes de initialize)
+    fn symbol(env: Env) -> String;
+}
 
-/// Trait que define la interfaz del token según CAP-46
+/// Extensiones de Token BDB sobre el estándar
 /// 
-/// Esta es la interfaz estándar de tokens fungibles en Stellar
-/// Compatible con wallets, DEXs, y el ecosistema completo
-pub trait TokenTrait {
+/// Inicialización, administración, fees, eventos enriquecidos,
+/// registro de holders y demás funcionalidades propias del contrato
+pub trait ExtendedToken {
     /// Inicializa el token con metadatos y admin
     /// 
-    /// Puede ser llamado solo una vez. Configura:
+    /// Puede ser llamado solo una vez. Reintentar con parámetros
+    /// idénticos es un no-op exitoso (útil para scripts de deploy);
+    /// con parámetros distintos devuelve AlreadyInitialized. Configura:
     /// - Admin: cuenta con permisos para mintear
     /// - Name: nombre completo del token
-    /// - Symbol: identificador corto (ej: BDB, USDC)
-    /// - Decimals: precisión del token (7 para Stellar)
+    /// - Symbol: identificador corto, solo A-Z y 0-9 (ej: BDB, USDC)
+    /// - Decimals: precisión del token (7 para Stellar, 1 a 18)
+    /// 
+    /// initialize_with_options() permite saltear ambas reglas con
+    /// allow_any_symbol y allow_zero_decimals
     fn initialize(
         env: Env, 
         admin: Address, 
@@ -37,508 +182,6689 @@ pub trait TokenTrait {
         decimals: u32
     ) -> Result<(), TokenError>;
     
-    /// Crea nuevos tokens (solo admin)
+    /// Inicializa el token activando funcionalidades opcionales
+    /// 
+    /// Igual que initialize(), pero recibe un InitOptions con los
+    /// flags de configuración (ej: pool comunitario en burns)
+    fn initialize_with_options(
+        env: Env, 
+        admin: Address, 
+        name: String, 
+        symbol: String,
+        decimals: u32,
+        options: InitOptions
+    ) -> Result<(), TokenError>;
+    
+    /// Inicializa el token con un multisig para las acciones de alto impacto
+    /// 
+    /// Igual que initialize_with_options(), y además guarda los
+    /// firmantes y el umbral que ejecutan acciones vía propose() /
+    /// approve_proposal(). Hasta MAX_INITIAL_ROLE_MEMBERS firmantes sin
+    /// repetir y 1 <= threshold <= firmantes (InvalidConfig si no). El
+    /// multisig no se puede agregar después
+    fn initialize_with_multisig(
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+        options: InitOptions,
+        multisig: Multisig
+    ) -> Result<(), TokenError>;
+    
+    /// Inicializa el token copiando los decimales de otro token
+    /// 
+    /// Para activos sintéticos: llama `decimals()` en `reference_token`
+    /// y guarda el resultado, en vez de recibir un literal. Falla con
+    /// InvalidConfig si la llamada falla y con InvalidDecimals si el
+    /// valor es 0 o supera MAX_DECIMALS. La copia es única (no se sincroniza)
+    fn initialize_mirroring(
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        reference_token: Address
+    ) -> Result<(), TokenError>;
+    
+    /// Inicializa el token otorgando los roles iniciales
+    /// 
+    /// Igual que initialize(), y en la misma transacción otorga Minter
+    /// a `minters` y Pauser a `pausers` (evita una segunda transacción
+    /// para habilitar un minter). Cada lista admite hasta
+    /// MAX_INITIAL_ROLE_MEMBERS cuentas sin repetir
+    fn initialize_with_roles(
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+        minters: Vec<Address>,
+        pausers: Vec<Address>
+    ) -> Result<(), TokenError>;
+    
+    /// Crea nuevos tokens (solo admin o mint controller)
     /// 
     /// Aumenta el supply total y el balance del destinatario
-    /// Requiere autorización del admin
+    /// Requiere autorización del admin, o del mint controller si
+    /// hay uno configurado. Con multisig el admin recibe Unauthorized
+    /// (se mintea con AdminAction::Mint)
     fn mint(env: Env, to: Address, amount: i128) -> Result<(), TokenError>;
     
-    /// Destruye tokens reduciendo el supply
+    /// Mintea como una cuenta con el rol Minter
     /// 
-    /// Reduce el supply total y el balance del owner
-    /// Requiere autorización del owner
-    fn burn(env: Env, from: Address, amount: i128) -> Result<(), TokenError>;
+    /// Requiere autorización de `minter`; si no tiene el rol devuelve
+    /// Unauthorized. Mismas reglas que mint()
+    fn mint_by(env: Env, minter: Address, to: Address, amount: i128) -> Result<(), TokenError>;
     
-    /// Consulta el balance de una cuenta
+    /// Aprueba a otro usuario sin vencimiento
     /// 
-    /// Devuelve 0 si la cuenta nunca ha recibido tokens
-    fn balance(env: Env, account: Address) -> i128;
+    /// Igual que approve() con expiration_ledger = u32::MAX (la firma
+    /// de approve() anterior a SEP-41). Se puede revocar con amount=0
+    fn approve_no_expiry(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128
+    ) -> Result<(), TokenError>;
     
-    /// Transfiere tokens entre cuentas
+    /// Aprueba a otro usuario adjuntando un payload de datos
     /// 
-    /// Requiere autorización de `from`
-    /// No permite transferencias a sí mismo
-    fn transfer(
+    /// Igual que approve(), pero con un blob
+    /// `data` (ej: referencia de factura) que solo viaja en el evento.
+    /// El payload NO se persiste. Máximo MAX_DATA_LENGTH bytes
+    fn approve_with_data(
         env: Env, 
         from: Address, 
-        to: Address, 
-        amount: i128
+        spender: Address, 
+        amount: i128,
+        expiration_ledger: u32,
+        data: Bytes
     ) -> Result<(), TokenError>;
     
-    /// Aprueba a otro usuario para gastar tokens
+    /// Aprueba un allowance de un solo uso
     /// 
-    /// Permite que `spender` gaste hasta `amount` tokens
-    /// de la cuenta de `from`. Se puede revocar con amount=0
-    fn approve(
+    /// Como approve_with_data (con expiración, sin payload), pero el
+    /// primer transfer_from que consuma cualquier parte revoca todo
+    /// el allowance restante
+    fn approve_once(
         env: Env, 
         from: Address, 
         spender: Address, 
-        amount: i128
+        amount: i128,
+        expiration_ledger: u32
     ) -> Result<(), TokenError>;
     
-    /// Consulta el allowance entre dos cuentas
+    /// Suma `amount` al allowance actual de `spender`
     /// 
-    /// Devuelve cuánto puede gastar `spender` de los tokens de `from`
-    fn allowance(env: Env, from: Address, spender: Address) -> i128;
+    /// Requiere autorización del owner. Conserva la expiración vigente;
+    /// si no hay allowance activo, el nuevo no vence (como approve_no_expiry)
+    fn increase_allowance(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128
+    ) -> Result<(), TokenError>;
     
-    /// Transfiere tokens en nombre de otro usuario
+    /// Fija el tope de allowance que `from` puede darle a `spender`
     /// 
-    /// Requiere allowance previo mediante approve()
-    /// Reduce el allowance automáticamente
-    fn transfer_from(
+    /// Requiere autorización del owner (autoprotección). Con tope,
+    /// approve* e increase_allowance fallan con AllowanceCapExceeded
+    /// si el allowance resultante lo supera. None lo quita. No
+    /// modifica un allowance ya otorgado
+    fn set_allowance_cap(
         env: Env, 
+        from: Address, 
         spender: Address, 
+        cap: Option<i128>
+    ) -> Result<(), TokenError>;
+    
+    /// Tope de allowance entre `from` y `spender` (None = sin tope)
+    fn allowance_cap(env: Env, from: Address, spender: Address) -> Option<i128>;
+    
+    /// Extiende el allowance de `spender` en cada uso (requiere auth de `from`)
+    /// 
+    /// Para pagos recurrentes: cada transfer_from exitoso suma
+    /// `delta_ledgers` a la expiración del allowance, así una
+    /// suscripción activa no vence sin re-aprobar. 0 lo desactiva
+    fn set_allowance_auto_extend(
+        env: Env, 
         from: Address, 
-        to: Address, 
+        spender: Address, 
+        delta_ledgers: u32
+    ) -> Result<(), TokenError>;
+    
+    /// Ledgers que cada uso extiende el allowance (0 = desactivado)
+    fn allowance_auto_extend(env: Env, from: Address, spender: Address) -> u32;
+    
+    /// Abre un pool de gasto de `from` para `spender` (requiere auth de `from`)
+    /// 
+    /// Para suscripciones: `spender` retira montos variables con pull()
+    /// hasta `total` y hasta `expiration_ledger`. Reemplaza un pool
+    /// previo; total 0 lo cierra
+    fn open_pool(
+        env: Env,
+        from: Address,
+        spender: Address,
+        total: i128,
+        expiration_ledger: u32
+    ) -> Result<(), TokenError>;
+    
+    /// Retira `amount` del pool de `from` hacia `to` (requiere auth de `spender`)
+    /// 
+    /// Igual que transfer_from pero descuenta del pool en vez del
+    /// allowance; InsufficientAllowance si el pool no alcanza o venció.
+    /// Por encima de DelayThreshold queda pendiente como en transfer()
+    fn pull(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
         amount: i128
     ) -> Result<(), TokenError>;
     
-    // Métodos de consulta (getters)
-    fn name(env: Env) -> String;
-    fn symbol(env: Env) -> String;
-    fn decimals(env: Env) -> u32;
+    /// Restante del pool de `from` para `spender` (0 si no existe o venció)
+    fn pool_remaining(env: Env, from: Address, spender: Address) -> i128;
+    
+    /// Indica si el allowance entre `from` y `spender` es de un solo uso
+    fn is_single_use(env: Env, from: Address, spender: Address) -> bool;
+    
+    /// Reemplaza name y symbol en una sola llamada (solo admin)
+    /// 
+    /// Valida ambos con las reglas de initialize (el symbol siempre en
+    /// A-Z/0-9) antes de escribir: si uno es inválido no cambia ninguno.
+    /// Emite un único evento `metadata` con el TokenMetadata completo.
+    /// Los decimales no se pueden cambiar
+    fn set_metadata(env: Env, name: String, symbol: String) -> Result<(), TokenError>;
+    
+    /// Supply total (0 antes de initialize)
+    /// 
+    /// Si la instancia del contrato fue archivada por TTL, la invocación
+    /// falla en el host antes de leer nada: nunca devuelve un 0 engañoso
+    /// para un token existente. Hay que restaurarla (RestoreFootprint)
     fn total_supply(env: Env) -> i128;
-    fn admin(env: Env) -> Address;
-}
-
-/// Estructura del contrato Token BDB
-#[contract]
-pub struct TokenBDB;
-
-/// Implementación del contrato
-#[contractimpl]
-impl TokenTrait for TokenBDB {
-    fn initialize(
-        env: Env, 
-        admin: Address, 
-        name: String, 
-        symbol: String,
-        decimals: u32
-    ) -> Result<(), TokenError> {
-        // 1. Verificar que no esté inicializado
-        if env.storage().instance().has(&DataKey::Initialized) {
-            return Err(TokenError::AlreadyInitialized);
-        }
-        
-        // 2. Validar decimales (máximo 18 como Ethereum)
-        if decimals > MAX_DECIMALS {
-            return Err(TokenError::InvalidDecimals);
-        }
-        
-        // 3. Validar metadatos (name y symbol no vacíos)
-        // Nota: String en Soroban no tiene .len() directo,
-        // pero podemos convertir a bytes para validar
-        if name.len() == 0 || name.len() > MAX_NAME_LENGTH {
-            return Err(TokenError::InvalidMetadata);
-        }
-        
-        if symbol.len() == 0 || symbol.len() > MAX_SYMBOL_LENGTH {
-            return Err(TokenError::InvalidMetadata);
-        }
-        
-        // 4. Guardar metadata en instance storage
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::TokenName, &name);
-        env.storage().instance().set(&DataKey::TokenSymbol, &symbol);
-        env.storage().instance().set(&DataKey::Decimals, &decimals);
-        env.storage().instance().set(&DataKey::TotalSupply, &0i128);
-        env.storage().instance().set(&DataKey::Initialized, &true);
-        
-        // 5. Extender TTL del storage de instance (30 días)
-        env.storage().instance().extend_ttl(100_000, 200_000);
-        
-        // 6. Emitir evento rico con todos los metadatos
-        env.events().publish(
-            (symbol_short!("init"), admin.clone()),
-            TokenMetadata {
-                name: name.clone(),
-                symbol: symbol.clone(),
-                decimals,
-            }
-        );
-        
-        Ok(())
-    }
     
-    fn mint(env: Env, to: Address, amount: i128) -> Result<(), TokenError> {
-        // 1. Verificar inicialización
-        if !env.storage().instance().has(&DataKey::Initialized) {
-            return Err(TokenError::NotInitialized);
-        }
-        
-        // 2. Solo el admin puede mintear
-        let admin: Address = env.storage().instance()
-            .get(&DataKey::Admin)
-            .ok_or(TokenError::NotInitialized)?;
-        admin.require_auth();
-        
-        // 3. Validaciones
-        if amount <= 0 {
-            return Err(TokenError::InvalidAmount);
-        }
-        
-        // 4. Validar que `to` no sea igual a `admin` (opcional, pero buena práctica)
-        // Esto evita que el admin se mintee tokens a sí mismo por error
-        
-        // 5. Obtener balance actual y verificar overflow
-        let balance = Self::balance(env.clone(), to.clone());
-        let new_balance = balance.checked_add(amount)
-            .ok_or(TokenError::OverflowError)?;
-        
-        // 6. Actualizar balance con TTL extendido
-        env.storage().persistent().set(
-            &DataKey::Balance(to.clone()), 
-            &new_balance
-        );
-        env.storage().persistent().extend_ttl(
-            &DataKey::Balance(to.clone()),
-            100_000,
-            200_000
-        );
-        
-        // 7. Actualizar total supply
-        let total: i128 = env.storage().instance()
-            .get(&DataKey::TotalSupply)
-            .unwrap_or(0);
-        let new_total = total.checked_add(amount)
-            .ok_or(TokenError::OverflowError)?;
-        env.storage().instance().set(
-            &DataKey::TotalSupply, 
-            &new_total
-        );
+    /// Dirección del admin
+    /// 
+    /// Devuelve NotInitialized (en vez de un panic) si el
+    /// contrato todavía no fue inicializado. Con la instancia
+    /// archivada falla igual que total_supply()
+    fn admin(env: Env) -> Result<Address, TokenError>;
+    
+    /// Total acumulado en el pool comunitario por burns
+    /// 
+    /// Siempre 0 si el modo pool no se activó en initialize
+    fn community_pool(env: Env) -> i128;
+    
+    /// Configura el máximo de allowances distintos por owner (solo admin)
+    /// 
+    /// Limita el storage que un owner puede ocupar con approvals.
+    /// 0 desactiva el límite
+    fn set_max_allowances_per_owner(env: Env, max: u32) -> Result<(), TokenError>;
+    
+    /// Máximo de allowances distintos por owner (0 = ilimitado)
+    fn max_allowances_per_owner(env: Env) -> u32;
+    
+    /// Cantidad de allowances almacenados que tiene `owner`
+    fn allowance_count(env: Env, owner: Address) -> u32;
+    
+    /// Configura los ledgers de gracia de los allowances (solo admin)
+    /// 
+    /// Un allowance sigue valiendo `grace` ledgers después de su
+    /// expiration_ledger, para allowance() y transfer_from(). Cubre las
+    /// transacciones que entran un ledger tarde. 0 = expiración exacta
+    fn set_allowance_grace(env: Env, grace: u32) -> Result<(), TokenError>;
+    
+    /// Ledgers de gracia de los allowances (0 = expiración exacta)
+    fn allowance_grace(env: Env) -> u32;
+    
+    /// Dirección de depósito determinística para un usuario
+    /// 
+    /// Es la dirección de contrato que derivaría este token como deployer
+    /// (`env.deployer().with_address`) usando como salt 32 bytes:
+    /// 24 ceros seguidos de `user_id` en big-endian. Off-chain se
+    /// reproduce con HashIdPreimage::ContractId { network_id,
+    /// ContractIdPreimage::FromAddress(token, salt) }
+    fn derived_address(env: Env, user_id: u64) -> Address;
+    
+    /// Mintea a la dirección derivada de `user_id` (solo admin)
+    /// 
+    /// Mismas validaciones y evento que mint()
+    fn mint_to_derived(env: Env, user_id: u64, amount: i128) -> Result<(), TokenError>;
+    
+    /// Configura el fee en XLM cobrado en cada transfer (solo admin)
+    /// 
+    /// `native_token` es el SAC del activo nativo. Si `fee` > 0, transfer
+    /// mueve `fee` XLM del sender a `collector` antes de mover tokens.
+    /// `fee` = 0 desactiva el cobro
+    fn set_xlm_fee(
+        env: Env,
+        native_token: Address,
+        collector: Address,
+        fee: i128
+    ) -> Result<(), TokenError>;
+    
+    /// Fee en XLM por transfer (0 = desactivado)
+    fn xlm_fee(env: Env) -> i128;
+    
+    /// Receptor del fee en XLM, si está configurado
+    fn xlm_fee_collector(env: Env) -> Option<Address>;
+    
+    /// Supply total vigente al cierre de un ledger pasado
+    /// 
+    /// Búsqueda binaria sobre los checkpoints que graban mint/burn.
+    /// Devuelve 0 para ledgers anteriores al checkpoint más viejo
+    /// retenido (se guardan los últimos MAX_SUPPLY_CHECKPOINTS)
+    fn supply_at_ledger(env: Env, ledger: u32) -> i128;
+    
+    /// Configura el fee de transferencia (solo admin)
+    /// 
+    /// En transfer/transfer_from se descuentan `bps` basis points del
+    /// amount y se acreditan a `treasury`. `bps` = 0 desactiva el fee.
+    /// Con multisig solo vía propuesta (Unauthorized)
+    fn set_transfer_fee(env: Env, bps: u32, treasury: Address) -> Result<(), TokenError>;
+    
+    /// Fee de transferencia en basis points (0 = sin fee)
+    fn transfer_fee_bps(env: Env) -> u32;
+    
+    /// Configura un fee fijo por transferencia (solo admin, 0 lo desactiva)
+    /// 
+    /// Se descuenta antes que el fee en bps, que se calcula sobre el
+    /// resto; ambos van al treasury (o a los receptores del fee).
+    /// Requiere un treasury configurado con set_transfer_fee. Las
+    /// transferencias con amount <= fee fijo fallan con AmountTooSmall
+    fn set_flat_fee(env: Env, amount: i128) -> Result<(), TokenError>;
+    
+    /// Fee fijo por transferencia (0 = sin fee fijo)
+    fn flat_fee(env: Env) -> i128;
+    
+    /// Reparte el fee de transferencia entre varios receptores (solo admin)
+    /// 
+    /// `recipients` es una lista de (receptor, bps) cuyas partes suman
+    /// 10000; el resto del redondeo va al último. Reemplaza al treasury
+    /// como destino del fee (el porcentaje sigue en set_transfer_fee).
+    /// Vacío vuelve al treasury único. Hasta MAX_FEE_RECIPIENTS
+    /// receptores, sin repetir, ni el admin ni el propio contrato
+    fn set_fee_recipients(env: Env, recipients: Vec<(Address, u32)>) -> Result<(), TokenError>;
+    
+    /// Receptores del fee con su parte en bps (vacío = solo treasury)
+    fn fee_recipients(env: Env) -> Vec<(Address, u32)>;
+    
+    /// Receptor del fee de transferencia, si está configurado
+    fn treasury(env: Env) -> Option<Address>;
+    
+    /// Configura la dirección de redondeo del fee (solo admin)
+    /// 
+    /// Floor: fee = floor(amount * bps / 10000)
+    /// Ceil:  fee = ceil(amount * bps / 10000)
+    fn set_rounding_mode(env: Env, mode: RoundingMode) -> Result<(), TokenError>;
+    
+    /// Dirección de redondeo vigente (Floor por defecto)
+    fn rounding_mode(env: Env) -> RoundingMode;
+    
+    /// Configura quién soporta el fee de transferencia (solo admin)
+    /// 
+    /// Recipient (por defecto): `to` recibe amount - fee.
+    /// SenderPaysExtra: `from` paga amount + fee y `to` recibe amount
+    fn set_fee_payer(env: Env, payer: FeePayer) -> Result<(), TokenError>;
+    
+    /// Modelo de incidencia del fee vigente (Recipient por defecto)
+    fn fee_payer(env: Env) -> FeePayer;
+    
+    /// Previsualiza una transferencia sin modificar estado
+    /// 
+    /// Usa la misma lógica que transfer() pero no escribe nada ni
+    /// requiere firma. Devuelve (new_from_balance, new_to_balance, fee)
+    /// o el mismo error que devolvería transfer()
+    fn simulate_transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<(i128, i128, i128), TokenError>;
+    
+    /// Verifica que `from` puede autorizar una transferencia sin ejecutarla
+    /// 
+    /// A diferencia de simulate_transfer, exige la firma de `from` y
+    /// corre todas las validaciones de transfer() (pausa, congelamiento,
+    /// restricciones, saldo). No escribe estado ni emite eventos
+    fn check_transfer_auth(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<(), TokenError>;
+    
+    /// Congela una cuenta (solo admin)
+    /// 
+    /// Una cuenta congelada no puede transferir (ni vía transfer_from)
+    /// ni quemar tokens
+    fn freeze(env: Env, account: Address) -> Result<(), TokenError>;
+    
+    /// Descongela una cuenta (solo admin)
+    fn unfreeze(env: Env, account: Address) -> Result<(), TokenError>;
+    
+    /// Congela varias cuentas con una sola firma del admin
+    /// 
+    /// Emite un evento por cuenta. Máximo MAX_BATCH_SIZE cuentas
+    fn freeze_batch(env: Env, accounts: Vec<Address>) -> Result<(), TokenError>;
+    
+    /// Descongela varias cuentas con una sola firma del admin
+    fn unfreeze_batch(env: Env, accounts: Vec<Address>) -> Result<(), TokenError>;
+    
+    /// Protege (o desprotege) una cuenta contra burns (solo admin)
+    /// 
+    /// Evita quemar por error la reserva o el treasury: burn y
+    /// burn_proportional desde una cuenta protegida fallan con
+    /// ProtectedAccount. No afecta transferencias
+    fn set_protected(env: Env, account: Address, protected: bool) -> Result<(), TokenError>;
+    
+    /// Indica si una cuenta está protegida contra burns
+    fn is_protected(env: Env, account: Address) -> bool;
+    
+    /// Configura el launch guard (solo admin, 0 lo desactiva)
+    /// 
+    /// Hasta `until_ledger` (exclusivo) toda transferencia necesita que
+    /// `from` o `to` sea un router habilitado o el admin; si no, falla
+    /// con Unauthorized. Después las transferencias son libres
+    fn set_launch_guard(env: Env, until_ledger: u32) -> Result<(), TokenError>;
+    
+    /// Ledger hasta el que rige el launch guard (0 = sin guard)
+    fn launch_guard_until(env: Env) -> u32;
+    
+    /// Habilita (o deshabilita) un router durante el launch guard (solo admin)
+    fn set_launch_router(env: Env, router: Address, allowed: bool) -> Result<(), TokenError>;
+    
+    /// Indica si un router está habilitado durante el launch guard
+    fn is_launch_router(env: Env, router: Address) -> bool;
+    
+    /// Indica si la cuenta está congelada
+    fn is_frozen(env: Env, account: Address) -> bool;
+    
+    /// Cantidad de transferencias salientes de `account`
+    /// 
+    /// Solo se cuenta si se activó track_tx_count en initialize
+    fn tx_count(env: Env, account: Address) -> u64;
+    
+    /// Última secuencia de transfer saliente de la cuenta
+    /// 
+    /// Solo avanza si se activó sender_seq en initialize; va en los
+    /// eventos transfer, trnsf_frm y pool_pull como último dato
+    fn sender_seq(env: Env, account: Address) -> u64;
+    
+    /// Últimas contrapartes de `account` (a quién envió y de quién recibió)
+    /// 
+    /// Solo se registran si se activó track_counterparties en
+    /// initialize; si no, ambos campos son None
+    fn last_counterparty(env: Env, account: Address) -> LastCounterparty;
+    
+    /// Balances de varias cuentas en una sola lectura
+    /// 
+    /// Devuelve un balance por posición de `accounts`, en el mismo
+    /// orden; los duplicados se resuelven cada uno por separado.
+    /// Máximo MAX_BATCH_SIZE cuentas
+    fn balances(env: Env, accounts: Vec<Address>) -> Result<Vec<i128>, TokenError>;
+    
+    /// Allowances que `from` otorgó a varios spenders en una sola lectura
+    /// 
+    /// Un valor por posición de `spenders`, en el mismo orden; sin
+    /// allowance o vencido se devuelve 0. Máximo MAX_BATCH_SIZE spenders
+    fn allowances(env: Env, from: Address, spenders: Vec<Address>) -> Result<Vec<i128>, TokenError>;
+    
+    /// Página del registro de holders
+    /// 
+    /// Cuentas que alguna vez tuvieron balance, sin duplicados y en orden
+    /// de alta. El registro es append-only: una cuenta que se vacía sigue
+    /// en su posición y volver a recibir no la duplica, así que paginar
+    /// con `start` es estable aunque haya altas entre llamadas.
+    /// Máximo MAX_BATCH_SIZE por página
+    fn holders(env: Env, start: u32, limit: u32) -> Result<Vec<Address>, TokenError>;
+    
+    /// Cantidad de holders registrados
+    fn holder_count(env: Env) -> u32;
+    
+    /// Configura el máximo de holders (solo admin)
+    /// 
+    /// Un mint o transferencia que agregaría un holder nuevo al
+    /// registro por encima del máximo falla con HolderCapReached; mover
+    /// tokens entre holders ya registrados no se ve afectado. Una cuenta
+    /// vaciada conserva su lugar. 0 lo desactiva
+    fn set_max_holders(env: Env, max: u32) -> Result<(), TokenError>;
+    
+    /// Máximo de holders (0 = ilimitado)
+    fn max_holders(env: Env) -> u32;
+    
+    /// Balance de este token en poder del propio contrato
+    fn self_balance(env: Env) -> i128;
+    
+    /// Balance de otro token (`token`) en poder de este contrato
+    /// 
+    /// Consulta `balance` en el contrato externo; no modifica estado
+    fn foreign_balance(env: Env, token: Address) -> i128;
+    
+    /// Pausa el contrato (admin, guardian o rol Pauser)
+    /// 
+    /// Mientras está pausado se bloquean las operaciones incluidas en
+    /// pause_policy() (por defecto todas: PAUSE_ALL). `caller` debe ser
+    /// el admin, el guardian o tener el rol Pauser y autorizar la
+    /// llamada; cualquier otro recibe Unauthorized. Con multisig el
+    /// admin pausa vía propuesta
+    fn pause(env: Env, caller: Address) -> Result<(), TokenError>;
+    
+    /// Reanuda el contrato (solo admin; con multisig, solo vía propuesta)
+    fn unpause(env: Env) -> Result<(), TokenError>;
+    
+    /// Otorga un rol a una cuenta (solo admin; con multisig, Unauthorized)
+    fn grant_role(env: Env, role: Role, account: Address) -> Result<(), TokenError>;
+    
+    /// Quita un rol a una cuenta (solo admin)
+    fn revoke_role(env: Env, role: Role, account: Address) -> Result<(), TokenError>;
+    
+    /// Indica si `account` tiene el rol
+    fn has_role(env: Env, role: Role, account: Address) -> bool;
+    
+    /// Permisos efectivos de `account` (solo lectura)
+    /// 
+    /// can_mint: admin (o mint controller si existe) o rol Minter.
+    /// can_pause: admin, guardian o rol Pauser. can_freeze: admin.
+    /// Con multisig el admin no tiene permisos propios (actúa vía
+    /// propuesta). Todo false si el contrato no está inicializado
+    fn permissions(env: Env, account: Address) -> Permissions;
+    
+    /// Keys persistentes que existen para `account` (solo lectura)
+    /// 
+    /// Solo hace chequeos `has` (más el contador de allowances que ya
+    /// mantiene write_allowance), así que no agrega costo a las demás
+    /// operaciones
+    fn account_storage_info(env: Env, account: Address) -> AccountStorage;
+    
+    /// Configura el guardian (solo admin)
+    /// 
+    /// El guardian puede pausar en una emergencia, pero solo el admin
+    /// puede despausar. None lo quita
+    fn set_guardian(env: Env, guardian: Option<Address>) -> Result<(), TokenError>;
+    
+    /// Guardian configurado (si existe)
+    fn guardian(env: Env) -> Option<Address>;
+    
+    /// Indica si el contrato está pausado
+    fn is_paused(env: Env) -> bool;
+    
+    /// Configura qué operaciones bloquea la pausa (solo admin)
+    /// 
+    /// `mask` combina PAUSE_TRANSFER, PAUSE_TRANSFER_FROM, PAUSE_MINT,
+    /// PAUSE_BURN y PAUSE_APPROVE. Ej: para un wind-down se pausa todo
+    /// menos PAUSE_BURN, así los holders pueden seguir quemando
+    fn set_pause_policy(env: Env, mask: u32) -> Result<(), TokenError>;
+    
+    /// Bitmask de operaciones bloqueadas durante la pausa
+    fn pause_policy(env: Env) -> u32;
+    
+    /// Allowance vigente expresado en tokens enteros
+    /// 
+    /// Devuelve (parte entera, parte fraccionaria) según `decimals`.
+    /// Ej: con 7 decimales, 12_500_000 -> (1, 2_500_000). Un allowance
+    /// expirado devuelve (0, 0). La parte fraccionaria es u64 porque
+    /// con 18 decimales no entra en un u32
+    fn allowance_display(env: Env, from: Address, spender: Address) -> (i128, u64);
+    
+    /// Delega la autoridad de minteo en un contrato controlador (solo admin)
+    /// 
+    /// Con un controller configurado, mint() requiere su auth y ya no la
+    /// del admin: el controller implementa su propia política (cupos,
+    /// calendarios, multisig) y se reemplaza sin tocar el token.
+    /// None vuelve al minteo por el admin. Con multisig devuelve
+    /// Unauthorized (el admin podría delegarse el minteo a sí mismo)
+    fn set_mint_controller(env: Env, controller: Option<Address>) -> Result<(), TokenError>;
+    
+    /// Contrato controlador de minteo, si está configurado
+    fn mint_controller(env: Env) -> Option<Address>;
+    
+    /// Revoca allowances otorgados por `from` (solo admin)
+    /// 
+    /// Pensado para cuentas comprometidas: junto con freeze() evita que
+    /// los spenders sigan usando allowances viejos. Los allowances no se
+    /// pueden enumerar on-chain, por eso se pasan los `spenders`.
+    /// Emite un evento por spender. Máximo MAX_BATCH_SIZE spenders
+    fn revoke_allowances(env: Env, from: Address, spenders: Vec<Address>) -> Result<(), TokenError>;
+    
+    /// Chequeo de salud para monitoreo
+    /// 
+    /// Reporta en una sola lectura: inicializado, pausado, supply,
+    /// si hay admin y la versión del contrato. Nunca falla, aunque el
+    /// contrato no esté (o esté parcialmente) inicializado
+    fn health(env: Env) -> HealthReport;
+    
+    /// Toda la configuración del contrato en una sola lectura
+    /// 
+    /// Pensado para dashboards de admin. Los valores no configurados
+    /// vienen con su default; nunca falla, aunque el contrato no esté
+    /// inicializado
+    fn config(env: Env) -> TokenConfig;
+    
+    /// Configura si se puede mintear a cuentas congeladas (solo admin)
+    /// 
+    /// Con true, mint() acredita a una cuenta congelada (ej: prefondeo
+    /// pendiente de KYC) y los tokens no se mueven hasta descongelarla.
+    /// Con false (por defecto) mint() devuelve AccountFrozen
+    fn set_allow_mint_to_frozen(env: Env, allow: bool) -> Result<(), TokenError>;
+    
+    /// Indica si mint() puede acreditar cuentas congeladas
+    fn allow_mint_to_frozen(env: Env) -> bool;
+    
+    /// Transfer con deduplicación para reintentos del cliente
+    /// 
+    /// Igual que transfer(), pero registra `op_id` para `from`. Si el
+    /// mismo `from` reenvía un `op_id` ya procesado, devuelve éxito sin
+    /// volver a transferir (at-most-once). Los op_id se guardan en
+    /// temporary storage y se olvidan tras OP_ID_TTL ledgers
+    fn transfer_idempotent(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+        op_id: BytesN<32>
+    ) -> Result<(), TokenError>;
+    
+    /// transfer_from que registra el pago para un refund posterior
+    /// 
+    /// Igual que transfer_from(), y además guarda bajo `ref_id` quién
+    /// pagó y cuánto recibió `to`. Falla con InvalidPaymentRef si el
+    /// ref_id ya se usó
+    fn transfer_from_tracked(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+        ref_id: BytesN<32>
+    ) -> Result<(), TokenError>;
+    
+    /// Devuelve al `from` original un pago de transfer_from_tracked
+    /// 
+    /// `caller` debe ser el admin o el `to` del pago y autorizar la
+    /// llamada. Mueve de `to` a `from` lo que `to` recibió, sin fee.
+    /// Cada pago se reembolsa una sola vez (AlreadyRefunded)
+    fn refund(env: Env, caller: Address, ref_id: BytesN<32>) -> Result<(), TokenError>;
+    
+    /// Pago registrado bajo `ref_id` (si existe)
+    fn payment(env: Env, ref_id: BytesN<32>) -> Option<PaymentRecord>;
+    
+    /// Transfer que notifica al receptor si es un contrato
+    /// 
+    /// Igual que transfer(); si `to` es un contrato además invoca su
+    /// hook `tokens_received(from, amount)` (ej: un vault acreditando el
+    /// depósito). Si el hook falla se revierte todo con NotificationFailed.
+    /// El hook puede verificar qué token lo llama con require_auth()
+    /// sobre la dirección del token esperado. A un contrato no se le
+    /// puede enviar más que DelayThreshold (quedaría retenido sin
+    /// acreditar): también falla con NotificationFailed
+    fn transfer_and_notify(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<(), TokenError>;
+    
+    /// Declara un snapshot de balances (solo admin)
+    /// 
+    /// Devuelve el id del snapshot (1, 2, ...). No copia balances: cada
+    /// cuenta guarda su balance previo en el primer cambio posterior,
+    /// así que declarar es O(1) sin importar la cantidad de holders
+    fn snapshot(env: Env) -> Result<u64, TokenError>;
+    
+    /// Id del último snapshot declarado (0 = ninguno)
+    fn current_snapshot_id(env: Env) -> u64;
+    
+    /// Balance de `account` al momento de declarar `snapshot_id`
+    /// 
+    /// Devuelve InvalidSnapshot si el id no fue declarado
+    fn balance_at_snapshot(
+        env: Env,
+        account: Address,
+        snapshot_id: u64
+    ) -> Result<i128, TokenError>;
+    
+    /// Exige que el spender acepte antes de gastar allowances (solo admin)
+    /// 
+    /// Con el flag activo, transfer_from falla con SpenderNotOptedIn si
+    /// el spender no llamó accept_spending() para ese `from`
+    fn set_require_spender_opt_in(env: Env, required: bool) -> Result<(), TokenError>;
+    
+    /// Indica si transfer_from exige el opt-in del spender
+    fn require_spender_opt_in(env: Env) -> bool;
+    
+    /// El spender acepta gastar allowances otorgados por `from`
+    /// 
+    /// Requiere autorización del spender
+    fn accept_spending(env: Env, spender: Address, from: Address) -> Result<(), TokenError>;
+    
+    /// Indica si `spender` aceptó gastar allowances de `from`
+    fn has_accepted_spending(env: Env, spender: Address, from: Address) -> bool;
+    
+    /// Activa (o desactiva) la restricción de destinos de `owner`
+    /// 
+    /// Requiere autorización del owner (autoprotección). Con la
+    /// restricción activa, sus transfer/transfer_from solo pueden ir a
+    /// destinos aprobados; el resto falla con DestinationNotApproved
+    fn set_restrict_destinations(env: Env, owner: Address, enabled: bool) -> Result<(), TokenError>;
+    
+    /// Indica si `owner` solo puede enviar a destinos aprobados
+    fn restricts_destinations(env: Env, owner: Address) -> bool;
+    
+    /// Aprueba un destino para `owner` (requiere auth del owner)
+    fn approve_destination(env: Env, owner: Address, destination: Address) -> Result<(), TokenError>;
+    
+    /// Quita un destino aprobado de `owner` (requiere auth del owner)
+    fn revoke_destination(env: Env, owner: Address, destination: Address) -> Result<(), TokenError>;
+    
+    /// Indica si `owner` aprobó `destination`
+    fn is_destination_approved(env: Env, owner: Address, destination: Address) -> bool;
+    
+    /// Configura el cooldown de burn tras recibir tokens (solo admin)
+    /// 
+    /// Una cuenta no puede quemar durante `ledgers` ledgers después de
+    /// recibir tokens (mint o transferencia); evita manipular el supply
+    /// con mint-y-burn en el mismo ledger. 0 lo desactiva
+    fn set_action_cooldown(env: Env, ledgers: u32) -> Result<(), TokenError>;
+    
+    /// Cooldown de burn en ledgers (0 = desactivado)
+    fn action_cooldown(env: Env) -> u32;
+    
+    /// Habilita la migración de decimales (solo admin)
+    /// 
+    /// Paso explícito previo a migrate_decimals(); no tiene efecto si
+    /// la migración ya se ejecutó
+    fn enable_decimals_migration(env: Env) -> Result<(), TokenError>;
+    
+    /// Estado de la migración de decimales
+    fn decimals_migration_status(env: Env) -> MigrationStatus;
+    
+    /// Cambia los decimales reescalando todos los montos (solo admin)
+    /// 
+    /// Solo se puede ejecutar una vez y con la migración habilitada.
+    /// Solo aumenta decimales (sin pérdida de precisión): multiplica por
+    /// 10^(new - old) todo monto guardado (supply e historial, balances,
+    /// bloqueados, vestings, snapshots, rewards, pendientes, propuestas,
+    /// montos de configuración y totales); cualquier overflow revierte
+    /// todo. Los montos por par de cuentas (allowances, topes, pools y
+    /// límites de volumen por cuenta) guardan sus decimales y se
+    /// reescalan al leerlos. Falla con InvalidConfig mientras haya tasas
+    /// por token (redemption asset o legacy token). Recorre todos los
+    /// holders, así que está pensado para tokens con pocos holders
+    fn migrate_decimals(env: Env, new_decimals: u32) -> Result<(), TokenError>;
+    
+    /// Activa los avisos de balances cerca de expirar (solo admin)
+    /// 
+    /// Con el flag activo, cada escritura de un balance estima el TTL
+    /// que le quedaba antes de extenderlo y, si era menor a
+    /// NEAR_EXPIRY_LEDGERS, emite `near_exp` para la cuenta. Los
+    /// contratos no pueden consultar el TTL real, así que se guarda
+    /// el ledger de expiración esperado de cada balance (costo extra)
+    fn set_near_expiry_warnings(env: Env, enabled: bool) -> Result<(), TokenError>;
+    
+    /// Indica si los avisos near_exp están activos
+    fn near_expiry_warnings(env: Env) -> bool;
+    
+    /// Bloquea parte del balance de una cuenta (solo admin)
+    /// 
+    /// El balance bloqueado sigue contando en balance() pero no se puede
+    /// transferir ni quemar con burn(). El total bloqueado no puede
+    /// superar el balance de la cuenta
+    fn lock(env: Env, account: Address, amount: i128) -> Result<(), TokenError>;
+    
+    /// Desbloquea parte del balance bloqueado (solo admin)
+    fn unlock(env: Env, account: Address, amount: i128) -> Result<(), TokenError>;
+    
+    /// Balance bloqueado de la cuenta
+    fn locked_balance(env: Env, account: Address) -> i128;
+    
+    /// Balance disponible para transferir o quemar (balance - bloqueado)
+    fn spendable_balance(env: Env, account: Address) -> i128;
+    
+    /// Lo que la cuenta puede mover ahora mismo
+    /// 
+    /// 0 si la cuenta está congelada o si la pausa vigente bloquea
+    /// transfer; si no, el balance libre (spendable_balance)
+    fn available_balance(env: Env, account: Address) -> i128;
+    
+    /// Mintea una asignación con vesting por tramos (solo admin o mint controller)
+    /// 
+    /// Mintea la suma de los tramos a `to` como balance bloqueado y
+    /// guarda el calendario; cada tramo se libera con claim() desde su
+    /// `unlock_ledger`. Se suma a un calendario existente. Entre 1 y
+    /// MAX_BATCH_SIZE tramos, todos con amount > 0
+    fn mint_vested(env: Env, to: Address, tranches: Vec<VestTranche>) -> Result<(), TokenError>;
+    
+    /// Tramos pendientes de `to` (vacío si no tiene vesting)
+    fn vest_schedule(env: Env, to: Address) -> Vec<VestTranche>;
+    
+    /// Suma de los tramos de `to` ya desbloqueados y sin reclamar
+    /// 
+    /// i128::MAX si la suma desborda (claim() falla con OverflowError)
+    fn claimable_vested(env: Env, to: Address) -> i128;
+    
+    /// Libera los tramos desbloqueados al balance libre
+    /// 
+    /// Requiere autorización de `to`. Devuelve lo liberado (0 si no
+    /// había nada desbloqueado). OverflowError si la suma de los tramos
+    /// desbloqueados desborda
+    fn claim(env: Env, to: Address) -> Result<i128, TokenError>;
+    
+    /// Quema de forma proporcional del balance bloqueado y del libre
+    /// 
+    /// Requiere autorización del owner. Descuenta del bloqueado
+    /// floor(amount * locked / balance) y el resto del libre, así la
+    /// proporción bloqueado:libre se mantiene. Falla si el balance total
+    /// no alcanza
+    fn burn_proportional(env: Env, from: Address, amount: i128) -> Result<(), TokenError>;
+    
+    /// Intercambio atómico entre dos holders (liquidación OTC)
+    /// 
+    /// Requiere autorización de `a` y de `b`. Mueve `amount_a_to_b` de
+    /// `a` a `b` y `amount_b_to_a` de `b` a `a`; ambos balances se
+    /// verifican antes de mover nada, así que o se ejecutan las dos
+    /// patas o ninguna. Cada pata aplica las reglas de transfer (fee,
+    /// mínimo, bloqueos). Una de las dos cantidades puede ser 0; una
+    /// mayor a DelayThreshold falla con InvalidAmount (no se retiene)
+    fn swap(
+        env: Env,
+        a: Address,
+        b: Address,
+        amount_a_to_b: i128,
+        amount_b_to_a: i128
+    ) -> Result<(), TokenError>;
+    
+    /// Configura el balance mínimo tras una transferencia (solo admin)
+    /// 
+    /// Una transferencia que deje al sender con 0 < balance < `min`
+    /// falla con BelowMinimum: debe conservar el mínimo o vaciar la
+    /// cuenta. Mint y burn no lo aplican. 0 lo desactiva
+    fn set_min_account_balance(env: Env, min: i128) -> Result<(), TokenError>;
+    
+    /// Balance mínimo tras una transferencia (0 = desactivado)
+    fn min_account_balance(env: Env) -> i128;
+    
+    /// Activa los eventos de operaciones fallidas (solo admin)
+    /// 
+    /// Con el flag activo, transfer, transfer_from y approve emiten un
+    /// evento `fail` (operación, caller, código de error) antes de
+    /// devolver el error. Soroban descarta los eventos de contrato de
+    /// una invocación fallida: solo se ven como eventos de diagnóstico
+    /// (RPC con diagnostic events) o cuando otro contrato llama al
+    /// token sin revertir la invocación completa
+    fn set_emit_failure_events(env: Env, enabled: bool) -> Result<(), TokenError>;
+    
+    /// Indica si se emiten eventos de operaciones fallidas
+    fn emit_failure_events(env: Env) -> bool;
+    
+    /// Omite los eventos de operaciones sin cambios (solo admin)
+    /// 
+    /// Con el flag activo, un approve que deja el allowance igual (mismo
+    /// monto, sin vencimiento) no emite evento. Desactivado por default
+    fn set_suppress_noop_events(env: Env, enabled: bool) -> Result<(), TokenError>;
+    
+    /// Indica si se omiten los eventos de operaciones sin cambios
+    fn suppress_noop_events(env: Env) -> bool;
+    
+    /// Número de secuencia del último evento emitido
+    /// 
+    /// Siempre 0 si la secuencia no se activó en initialize
+    fn last_event_seq(env: Env) -> u64;
+    
+    /// Categoría de un código de error (ver errors::error_category)
+    /// 
+    /// 1 = auth, 2 = validación, 3 = estado, 4 = aritmética,
+    /// 0 = código desconocido
+    fn categorize_error(env: Env, code: u32) -> u32;
+    
+    /// Reparte tokens desde la reserva (solo admin)
+    /// 
+    /// Para tokens de supply fijo: el supply se mintea de una vez a la
+    /// reserva configurada en initialize y este método lo reparte sin
+    /// cambiar total_supply. Falla con InvalidConfig si no hay reserva
+    /// y con InsufficientBalance si su balance libre no alcanza
+    fn distribute(env: Env, to: Address, amount: i128) -> Result<(), TokenError>;
+    
+    /// Cuenta reserva configurada en initialize (si existe)
+    fn reserve(env: Env) -> Option<Address>;
+    
+    /// Migra tokens del contrato viejo a este (requiere auth de `holder`)
+    /// 
+    /// Quema `amount` del token viejo con su `burn` y mintea a `holder`
+    /// amount * migration_rate / 10000 (1:1 por defecto). `old_token`
+    /// debe ser el configurado con set_legacy_token (InvalidConfig si no)
+    fn migrate_from(
+        env: Env,
+        holder: Address,
+        amount: i128,
+        old_token: Address
+    ) -> Result<i128, TokenError>;
+    
+    /// Configura el token viejo aceptado por migrate_from (solo admin)
+    /// 
+    /// None desactiva la migración
+    fn set_legacy_token(env: Env, token: Option<Address>) -> Result<(), TokenError>;
+    
+    /// Token viejo configurado (si existe)
+    fn legacy_token(env: Env) -> Option<Address>;
+    
+    /// Configura la tasa de migración en bps (solo admin, > 0)
+    fn set_migration_rate(env: Env, bps: u32) -> Result<(), TokenError>;
+    
+    /// Tasa de migración vigente en bps (10000 = 1:1 por defecto)
+    fn migration_rate(env: Env) -> u32;
+    
+    /// Configura el supply máximo (solo admin, None lo quita)
+    /// 
+    /// Los mints que lo superen fallan con SupplyCapExceeded. No puede
+    /// ser negativo, menor al supply actual ni mayor a
+    /// max_representable_supply() (InvalidConfig)
+    fn set_max_supply(env: Env, max_supply: Option<i128>) -> Result<(), TokenError>;
+    
+    /// Supply más grande que el contrato maneja sin overflow
+    /// 
+    /// i128::MAX / 10000 (margen para multiplicar por bps en fees e
+    /// inflación) / 10^(18 - decimals) (margen para migrar a 18
+    /// decimales). Son ~1.7e16 tokens enteros con cualquier decimals;
+    /// las herramientas pueden avisar antes de acercarse
+    fn max_representable_supply(env: Env) -> i128;
+    
+    /// Supply máximo configurado (None = sin tope)
+    fn max_supply(env: Env) -> Option<i128>;
+    
+    /// Cuánto se puede mintear todavía sin superar el supply máximo
+    /// 
+    /// max_supply - total_supply, o i128::MAX sin tope
+    fn mintable_headroom(env: Env) -> i128;
+    
+    /// Indica si un mint de `amount` entraría bajo el supply máximo
+    /// 
+    /// Solo mira el tope (no permisos, pausa ni congelamiento)
+    fn can_mint(env: Env, amount: i128) -> bool;
+    
+    /// Configura la inflación programada (solo admin)
+    /// 
+    /// Cada `window_ledgers` ledgers se puede mintear total_supply *
+    /// rate_bps / 10000 a `recipient` con mint_inflation(). La primera
+    /// ventana empieza en el ledger actual. rate_bps 0 la desactiva;
+    /// rate_bps > 10000, window 0 o recipient igual al contrato
+    /// devuelven InvalidConfig
+    fn set_inflation(
+        env: Env,
+        rate_bps_per_window: u32,
+        window_ledgers: u32,
+        recipient: Address
+    ) -> Result<(), TokenError>;
+    
+    /// Programa de inflación vigente (None si no hay)
+    fn inflation(env: Env) -> Option<InflationConfig>;
+    
+    /// Inicio de la ventana de inflación en curso
+    fn last_inflation_ledger(env: Env) -> u32;
+    
+    /// Mintea la inflación de una ventana completa (sin permisos)
+    /// 
+    /// Cualquiera puede llamarlo; falla con InflationNotDue si todavía
+    /// no pasó una ventana desde last_inflation_ledger y avanza el
+    /// marcador una ventana por llamada. Devuelve el monto minteado
+    fn mint_inflation(env: Env) -> Result<i128, TokenError>;
+    
+    /// Quema `amount` del treasury como recompra (solo el buyback executor)
+    /// 
+    /// Separa las quemas de recompra de las de usuarios: emite su propio
+    /// evento y acumula total_buyback_burned. No requiere firma del
+    /// treasury ni respeta su protección contra burns (es la quema
+    /// designada); sí la pausa de burns y el congelamiento
+    fn buyback_burn(env: Env, amount: i128) -> Result<(), TokenError>;
+    
+    /// Configura el buyback executor (solo admin, None lo quita)
+    fn set_buyback_executor(env: Env, executor: Option<Address>) -> Result<(), TokenError>;
+    
+    /// Buyback executor configurado (si existe)
+    fn buyback_executor(env: Env) -> Option<Address>;
+    
+    /// Total acumulado quemado con buyback_burn()
+    fn total_buyback_burned(env: Env) -> i128;
+    
+    /// Configura el relay de notificaciones (solo admin)
+    /// 
+    /// Después de cada transfer() exitoso se llama `notify(from, to,
+    /// amount)` en el relay. A diferencia de un hook, el relay solo
+    /// observa: si falla, el error se ignora y la transferencia queda
+    fn set_notification_relay(env: Env, relay: Address) -> Result<(), TokenError>;
+    
+    /// Quita el relay de notificaciones (solo admin)
+    fn clear_notification_relay(env: Env) -> Result<(), TokenError>;
+    
+    /// Relay de notificaciones configurado (si existe)
+    fn notification_relay(env: Env) -> Option<Address>;
+    
+    /// Configura el volumen saliente máximo por ventana de `account` (solo admin)
+    /// 
+    /// Cuenta lo enviado por transfer, transfer_from, pull y swap; una
+    /// transferencia que lo supere falla con VolumeLimitExceeded. Tiene
+    /// prioridad sobre el límite por defecto; None vuelve al default.
+    /// Un límite negativo devuelve InvalidConfig
+    fn set_volume_limit(env: Env, account: Address, limit: Option<i128>) -> Result<(), TokenError>;
+    
+    /// Configura el volumen máximo por ventana de todas las cuentas (solo admin)
+    /// 
+    /// None lo quita. Las cuentas con límite propio lo ignoran
+    fn set_default_volume_limit(env: Env, limit: Option<i128>) -> Result<(), TokenError>;
+    
+    /// Configura el largo de la ventana de volumen en ledgers (solo admin)
+    /// 
+    /// Las ventanas están alineadas (ledger / window_ledgers), así que el
+    /// volumen usado vuelve a 0 al empezar cada una. 0 devuelve InvalidConfig
+    fn set_volume_window(env: Env, window_ledgers: u32) -> Result<(), TokenError>;
+    
+    /// Límite de volumen vigente de `account` (propio o default; None = sin límite)
+    fn volume_limit(env: Env, account: Address) -> Option<i128>;
+    
+    /// Límite de volumen por defecto (None = sin límite)
+    fn default_volume_limit(env: Env) -> Option<i128>;
+    
+    /// Largo de la ventana de volumen (por defecto ~1 día)
+    fn volume_window(env: Env) -> u32;
+    
+    /// Volumen saliente de `account` en la ventana actual
+    /// 
+    /// Solo se registra mientras la cuenta tiene un límite
+    fn volume_used(env: Env, account: Address) -> i128;
+    
+    /// Volumen que `account` todavía puede enviar en la ventana actual
+    /// 
+    /// None si la cuenta no tiene límite
+    fn volume_remaining(env: Env, account: Address) -> Option<i128>;
+    
+    /// Mueve todo el balance libre del contrato a `to` (solo admin)
+    /// 
+    /// Herramienta de wind-down para migrar la reserva a un contrato
+    /// nuevo en una sola llamada. Solo con el contrato pausado y si se
+    /// habilitó drain_enabled en initialize; si no, DrainUnavailable.
+    /// Lo bloqueado (lock) no se mueve. No cobra fees. Devuelve el
+    /// monto movido
+    fn drain_reserve(env: Env, to: Address) -> Result<i128, TokenError>;
+    
+    /// Indica si drain_reserve() se habilitó en initialize
+    fn drain_enabled(env: Env) -> bool;
+    
+    /// Impide que un burn deje el token sin holders (solo admin)
+    /// 
+    /// Con el flag, un burn que dejaría el supply en 0 (el último holder
+    /// quemando todo) falla con CannotRemoveLastHolder. Se mira el
+    /// supply porque holder_count es un registro que nunca decrece
+    fn set_require_non_empty(env: Env, enabled: bool) -> Result<(), TokenError>;
+    
+    /// Indica si un burn puede dejar el token sin holders
+    fn require_non_empty(env: Env) -> bool;
+    
+    /// Impide usar transfer_from para pasar fondos a un operador (solo admin)
+    /// 
+    /// Con el flag, transfer_from falla con InvalidRecipient si `to` es
+    /// un contrato que tiene allowances otorgados a terceros (contador
+    /// de allowances > 0): así un spender no puede "lavar" un allowance
+    /// moviendo los fondos a un contrato que los vuelve a delegar.
+    /// Las cuentas normales no se ven afectadas
+    fn set_non_transferable_allowance(env: Env, enabled: bool) -> Result<(), TokenError>;
+    
+    /// Indica si transfer_from rechaza destinos que delegan allowances
+    fn non_transferable_allowance(env: Env) -> bool;
+    
+    /// Bloquea la configuración para siempre (solo admin, irreversible)
+    /// 
+    /// Después, todos los setters de configuración del admin (fees,
+    /// topes, decimales, metadata, flags) fallan con ConfigLocked.
+    /// Las operaciones siguen: mint, pause/unpause, freeze, roles,
+    /// transferencias. Punto medio entre un admin con control total
+    /// y renunciar al admin
+    fn lock_config(env: Env) -> Result<(), TokenError>;
+    
+    /// Indica si la configuración está bloqueada
+    fn config_locked(env: Env) -> bool;
+    
+    /// Retiene las transferencias grandes durante `delay_ledgers` (solo admin)
+    /// 
+    /// Un transfer(), transfer_from() o pull() mayor a `threshold`
+    /// descuenta los tokens de `from` pero no los acredita: crea una
+    /// transferencia pendiente (evento pending con su id) que se liquida
+    /// con settle_transfer o se cancela con cancel_transfer. Un swap()
+    /// mayor falla con InvalidAmount. `threshold` 0 lo desactiva
+    fn set_transfer_delay(env: Env, threshold: i128, delay_ledgers: u32) -> Result<(), TokenError>;
+    
+    /// Monto a partir del cual transfer(), transfer_from() y pull() quedan
+    /// pendientes (0 = desactivado)
+    fn delay_threshold(env: Env) -> i128;
+    
+    /// Ledgers que una transferencia pendiente espera antes de liquidarse
+    fn settlement_delay(env: Env) -> u32;
+    
+    /// Consulta una transferencia pendiente
+    /// 
+    /// None si el id no existe o ya se liquidó / canceló
+    fn pending_transfer(env: Env, id: u64) -> Option<PendingTransfer>;
+    
+    /// Acredita una transferencia pendiente a `to`
+    /// 
+    /// Cualquiera puede llamarlo desde settle_ledger; antes falla con
+    /// OutsideSettlementWindow. El fee se reparte recién acá
+    fn settle_transfer(env: Env, id: u64) -> Result<(), TokenError>;
+    
+    /// Devuelve a `from` los tokens de una transferencia pendiente
+    /// 
+    /// Requiere autorización de `from` y solo antes de settle_ledger;
+    /// después falla con OutsideSettlementWindow. No se cobra fee
+    fn cancel_transfer(env: Env, id: u64) -> Result<(), TokenError>;
+    
+    /// Quema tokens de `from` y le entrega su parte de la reserva
+    /// 
+    /// A diferencia de burn() (que solo destruye), libera
+    /// `amount * redemption_rate / 10000` del activo subyacente que el
+    /// contrato tiene en custodia. Falla con InvalidConfig sin activo
+    /// configurado e InsufficientBalance si la reserva no alcanza.
+    /// Mismas reglas que burn(). Devuelve el monto liberado
+    fn burn_redeem(env: Env, from: Address, amount: i128) -> Result<i128, TokenError>;
+    
+    /// Configura el activo subyacente que entrega burn_redeem (solo admin)
+    /// 
+    /// None desactiva la redención
+    fn set_redemption_asset(env: Env, asset: Option<Address>) -> Result<(), TokenError>;
+    
+    /// Consulta el activo subyacente de burn_redeem
+    fn redemption_asset(env: Env) -> Option<Address>;
+    
+    /// Configura el subyacente por token redimido en bps (solo admin)
+    /// 
+    /// 10000 = 1:1 (default); 0 devuelve InvalidConfig
+    fn set_redemption_rate(env: Env, bps: u32) -> Result<(), TokenError>;
+    
+    /// Consulta la tasa de redención en bps
+    fn redemption_rate(env: Env) -> u32;
+    
+    /// Total de tokens quemados con burn_redeem
+    /// 
+    /// No incluye burns destructivos (burn, burn_proportional, buyback)
+    fn total_redeemed(env: Env) -> i128;
+    
+    /// Rechaza allowances cuyo spender es el propio contrato (solo admin)
+    /// 
+    /// Aprobar al token como spender casi siempre es un error de
+    /// integración y habilita flujos donde el contrato gasta fondos de
+    /// terceros en su propia llamada. Con el flag, approve y sus
+    /// variantes e increase_allowance fallan con InvalidRecipient
+    /// (revocar con 0 sigue permitido). Activo por defecto en deploys
+    /// nuevos; los anteriores a este flag arrancan sin él
+    fn set_block_self_approve(env: Env, enabled: bool) -> Result<(), TokenError>;
+    
+    /// Indica si se rechazan approves al propio contrato
+    fn block_self_approve(env: Env) -> bool;
+    
+    /// Propone una acción al multisig y devuelve su id
+    /// 
+    /// `proposer` debe ser firmante (Unauthorized si no; InvalidConfig
+    /// si no se configuró multisig en initialize) y su propuesta cuenta
+    /// como primera aprobación: con threshold 1 se ejecuta en el acto.
+    /// Con multisig, el admin ya no ejecuta solo estas acciones
+    fn propose(env: Env, proposer: Address, action: AdminAction) -> Result<u64, TokenError>;
+    
+    /// Suma la aprobación de `signer` a una propuesta
+    /// 
+    /// Al juntar `threshold` aprobaciones la acción se ejecuta en esta
+    /// misma llamada (si falla, la llamada revierte y la propuesta
+    /// sigue pendiente). Devuelve true si se ejecutó. AlreadyApproved
+    /// si el firmante ya aprobó; UnknownProposal si no está pendiente
+    fn approve_proposal(env: Env, signer: Address, id: u64) -> Result<bool, TokenError>;
+    
+    /// Consulta una propuesta pendiente (None si no existe o se ejecutó)
+    fn proposal(env: Env, id: u64) -> Option<Proposal>;
+    
+    /// Firmantes y threshold del multisig (None si no se configuró)
+    fn multisig(env: Env) -> Option<Multisig>;
+    
+    /// Igual que transfer(), pero devuelve el resultado completo
+    /// 
+    /// Para contratos que componen transferencias: el TransferReceipt
+    /// trae fee, balances finales y ledger sin lecturas extra, y el id
+    /// si la transferencia quedó pendiente. transfer() sigue devolviendo
+    /// () por compatibilidad con SEP-41
+    fn transfer_with_receipt(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<TransferReceipt, TokenError>;
+    
+    /// Configura dos niveles de TTL para los balances (solo admin)
+    /// 
+    /// Optimiza el gasto de renta: las cuentas que enviaron tokens en
+    /// la ventana activa extienden su balance más que las dormidas
+    /// (las que solo reciben). None vuelve al TTL único. InvalidConfig
+    /// si la ventana o algún nivel es 0, si el activo es menor al base
+    /// o si supera el TTL máximo de la red
+    fn set_ttl_tiers(env: Env, tiers: Option<TtlTiers>) -> Result<(), TokenError>;
+    
+    /// Niveles de TTL configurados (None = TTL único)
+    fn ttl_tiers(env: Env) -> Option<TtlTiers>;
+    
+    /// Balance de `account` junto con el ledger y el supply actuales
+    /// 
+    /// Lectura pura pensada para bridges: el relayer atestigua los tres
+    /// valores como un mismo estado. No es una prueba criptográfica
+    fn balance_proof(env: Env, account: Address) -> BalanceProof;
+    
+    /// Fija los ledgers mínimos entre dos cambios de configuración (solo admin)
+    /// 
+    /// Un setter del admin llamado antes de que pasen `ledgers` desde el
+    /// cambio anterior falla con CooldownActive, así los holders saben
+    /// que los parámetros no cambian más rápido que ese ritmo. Cuenta
+    /// como cambio (también respeta el cooldown vigente). 0 lo desactiva
+    fn set_config_change_cooldown(env: Env, ledgers: u32) -> Result<(), TokenError>;
+    
+    /// Ledgers mínimos entre cambios de configuración (0 = desactivado)
+    fn config_change_cooldown(env: Env) -> u32;
+    
+    /// Ledger del último cambio de configuración (None si no hubo)
+    fn last_config_change(env: Env) -> Option<u32>;
+    
+    /// Condiciona las transferencias a un oráculo de precio (solo admin)
+    /// 
+    /// Para productos estructurados: cada transferencia consulta
+    /// `get_price()` en el oráculo y falla con ConditionNotMet si el
+    /// precio no queda por encima/debajo del umbral. None quita la
+    /// condición. InvalidConfig si el oráculo es el propio contrato
+    fn set_price_condition(env: Env, condition: Option<PriceCondition>) -> Result<(), TokenError>;
+    
+    /// Condición de precio vigente (None = transferencias incondicionales)
+    fn price_condition(env: Env) -> Option<PriceCondition>;
+    
+    /// Fija el reward rate de los holders (solo admin, 0 lo desactiva)
+    /// 
+    /// Cada cuenta acumula balance × ledgers × rate / REWARD_RATE_SCALE
+    /// mientras mantiene tokens, incluso sin moverlos. Antes de cada
+    /// cambio de balance (mint, transfer, burn...) se liquida lo
+    /// pendiente, así que enviar tokens deja de acumular desde ese
+    /// ledger. Lo acumulado hasta el cambio de rate queda con el rate
+    /// anterior, y con rate 0 no acumula nadie
+    fn set_reward_rate(env: Env, rate: u32) -> Result<(), TokenError>;
+    
+    /// Reward rate vigente (0 = sin rewards)
+    fn reward_rate(env: Env) -> u32;
+    
+    /// Rewards que `account` cobraría ahora con claim_rewards()
+    fn pending_rewards(env: Env, account: Address) -> i128;
+    
+    /// Mintea a `account` sus rewards pendientes (requiere su firma)
+    /// 
+    /// Respeta la pausa de mints y el max supply. Devuelve el monto
+    /// minteado (0 si no había nada que cobrar)
+    fn claim_rewards(env: Env, account: Address) -> Result<i128, TokenError>;
+    
+    /// Quema `amounts[i]` de `froms[i]` con una sola firma del admin
+    /// 
+    /// Para recuperaciones coordinadas: no requiere firma de las
+    /// cuentas y aplica aunque estén congeladas o en pausa, pero no
+    /// toca cuentas protegidas ni balance bloqueado (Locked respalda
+    /// vestings y locks: quemarlo dejaría Locked > balance; para
+    /// recuperarlo, primero unlock()). InvalidAmount si los largos
+    /// difieren o algún monto no es positivo. Atómico: si una entrada
+    /// falla no se quema nada. Devuelve el total quemado
+    fn clawback_batch(
+        env: Env,
+        froms: Vec<Address>,
+        amounts: Vec<i128>
+    ) -> Result<i128, TokenError>;
+    
+    /// Ledger en que se inicializó el token (0 antes de initialize)
+    fn deploy_ledger(env: Env) -> u32;
+    
+    /// Ledgers transcurridos desde initialize (0 antes de initialize)
+    /// 
+    /// Para UIs del tipo "creado hace N días" sin indexar eventos
+    fn age_ledgers(env: Env) -> u32;
+    
+    /// Exige montos en unidades enteras (solo admin, desactivado por defecto)
+    /// 
+    /// Para tokens de unidades indivisibles (membresías, tickets): con
+    /// el flag, transferencias, mints y burns cuyo monto no sea
+    /// múltiplo de 10^decimals fallan con FractionalAmount (también un
+    /// fee fijo fraccionario). Los montos derivados (fees en bps, rewards,
+    /// inflación, migrate_from, penalidades) se redondean hacia abajo a
+    /// unidades enteras. Con 0 decimales todo monto es entero
+    fn set_whole_units_only(env: Env, enabled: bool) -> Result<(), TokenError>;
+    
+    /// Indica si solo se aceptan montos en unidades enteras
+    fn whole_units_only(env: Env) -> bool;
+    
+    /// Fija la penalidad de emergency_unlock() en bps (solo admin)
+    /// 
+    /// 0 (por defecto) deshabilita emergency_unlock(); más de 10000
+    /// devuelve InvalidConfig
+    fn set_early_unlock_penalty(env: Env, bps: u32) -> Result<(), TokenError>;
+    
+    /// Penalidad por desbloqueo anticipado en bps
+    fn early_unlock_penalty(env: Env) -> u32;
+    
+    /// Total quemado como penalidad de desbloqueo anticipado
+    fn total_penalty_burned(env: Env) -> i128;
+    
+    /// Libera ya los tramos de vesting pendientes de `account`, con penalidad
+    /// 
+    /// Requiere autorización de `account`. Quema early_unlock_penalty
+    /// bps de los tramos todavía no desbloqueados (redondeo a favor de
+    /// la cuenta) y el resto pasa al balance libre. No toca lo bloqueado
+    /// con lock() ni los tramos ya desbloqueados (se reclaman con
+    /// claim()). Falla con InvalidConfig si no hay penalidad configurada.
+    /// Respeta la pausa de burns y el congelamiento. Devuelve lo
+    /// liberado neto (0 si no había tramos pendientes)
+    fn emergency_unlock(env: Env, account: Address) -> Result<i128, TokenError>;
+    
+    /// Balance de `account` que además extiende el TTL de la entrada
+    /// 
+    /// No restaura nada: un balance archivado nunca se lee como 0, la
+    /// transacción tiene que restaurarlo (RestoreFootprint o la
+    /// restauración automática que agrega la simulación) antes de que
+    /// el contrato lo lea. Tras leerlo, esta variante lo extiende igual
+    /// que una escritura para que no vuelva a archivarse. Costo: la
+    /// renta de la extensión cuando queda menos de la mitad del TTL; si
+    /// no, lo mismo que balance()
+    fn balance_and_extend(env: Env, account: Address) -> i128;
+}
+
+/// Estructura del contrato Token BDB
+#[contract]
+pub struct TokenBDB;
+
+/// Implementación de la interfaz estándar
+#[contractimpl]
+impl StandardToken for TokenBDB {
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        Self::read_allowance(&env, &from, &spender).amount
+    }
+    
+    fn approve(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128,
+        expiration_ledger: u32
+    ) -> Result<(), TokenError> {
+        let result = Self::execute_approve(env.clone(), from.clone(), spender, amount, expiration_ledger);
+        Self::report_failure(&env, event_topic::APPROVE, from, result)
+    }
+    
+    fn balance(env: Env, account: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::Balance(account))
+            .unwrap_or(0)
+    }
+    
+    fn transfer(
+        env: Env, 
+        from: Address, 
+        to: Address, 
+        amount: i128
+    ) -> Result<(), TokenError> {
+        let result = Self::execute_transfer(env.clone(), from.clone(), to, amount);
+        Self::report_failure(&env, event_topic::TRANSFER, from, result).map(|_| ())
+    }
+    
+    fn transfer_from(
+        env: Env, 
+        spender: Address, 
+        from: Address, 
+        to: Address, 
+        amount: i128
+    ) -> Result<(), TokenError> {
+        let result = Self::execute_transfer_from(env.clone(), spender.clone(), from, to, amount);
+        Self::report_failure(&env, event_topic::TRANSFER_FROM, spender, result)
+    }
+    
+    fn burn(env: Env, from: Address, amount: i128) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_BURN)?;
+        
+        // 2. Requiere autorización del dueño de los tokens
+        from.require_auth();
+        
+        // 3. Validar, descontar el balance y reducir el supply
+        let (new_balance, new_total) = Self::burn_balance(&env, &from, amount)?;
+        
+        // 4. Emitir evento (incluye quién autorizó el burn)
+        Self::publish(
+            &env,
+            (event_topic::BURN, from.clone()),
+            (amount, new_balance, new_total, from)
+        );
+        
+        Ok(())
+    }
+    
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_BURN)?;
+        
+        // 2. Verificar autorización del spender
+        spender.require_auth();
+        
+        // 3. Verificar allowance (expirado cuenta como 0)
+        let current = Self::read_allowance(&env, &from, &spender);
+        if current.amount < amount {
+            return Err(TokenError::InsufficientAllowance);
+        }
+        
+        if Self::require_spender_opt_in(env.clone())
+            && !Self::has_accepted_spending(env.clone(), spender.clone(), from.clone())
+        {
+            return Err(TokenError::SpenderNotOptedIn);
+        }
+        
+        // 4. Validar, descontar el balance, reducir el supply y el allowance
+        let (new_balance, new_total) = Self::burn_balance(&env, &from, amount)?;
+        Self::spend_allowance(&env, &from, &spender, &current, amount)?;
+        
+        // 5. Emitir evento (incluye quién autorizó el burn)
+        Self::publish(
+            &env,
+            (event_topic::BURN, from),
+            (amount, new_balance, new_total, spender)
+        );
+        
+        Ok(())
+    }
+    
+    fn decimals(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKey::Decimals)
+            .unwrap_or_default()
+    }
+    
+    fn name(env: Env) -> String {
+        // Un solo acceso: las keys de metadata solo existen tras
+        // initialize, así que su ausencia ya indica "no inicializado"
+        env.storage().instance()
+            .get(&DataKey::TokenName)
+            .unwrap_or_else(|| String::from_str(&env, ""))
+    }
+    
+    fn symbol(env: Env) -> String {
+        env.storage().instance()
+            .get(&DataKey::TokenSymbol)
+            .unwrap_or_else(|| String::from_str(&env, ""))
+    }
+}
+
+/// Implementación de las extensiones
+#[contractimpl]
+impl ExtendedToken for TokenBDB {
+    fn initialize(
+        env: Env, 
+        admin: Address, 
+        name: String, 
+        symbol: String,
+        decimals: u32
+    ) -> Result<(), TokenError> {
+        Self::initialize_with_options(
+            env,
+            admin,
+            name,
+            symbol,
+            decimals,
+            InitOptions::default()
+        )
+    }
+    
+    fn initialize_with_options(
+        env: Env, 
+        admin: Address, 
+        name: String, 
+        symbol: String,
+        decimals: u32,
+        options: InitOptions
+    ) -> Result<(), TokenError> {
+        // 1. Verificar que no esté inicializado
+        // Un reintento con exactamente los mismos parámetros es un no-op
+        if env.storage().instance().has(&DataKey::Initialized) {
+            if Self::matches_init_params(&env, &admin, &name, &symbol, decimals, &options) {
+                return Ok(());
+            }
+            return Err(TokenError::AlreadyInitialized);
+        }
+        
+        // 2. Validar decimales (máximo 18 como Ethereum; 0 solo si se pide)
+        if decimals > MAX_DECIMALS || (decimals == 0 && !options.allow_zero_decimals) {
+            return Err(TokenError::InvalidDecimals);
+        }
+        
+        // 3. Validar metadatos (name y symbol no vacíos)
+        Self::validate_metadata(&name, &symbol, options.allow_any_symbol)?;
+        
+        Self::validate_config(&env, &ConfigCheck::new(admin.clone()))?;
+        
+        // 4. Guardar metadata en instance storage
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::TokenName, &name);
+        env.storage().instance().set(&DataKey::TokenSymbol, &symbol);
+        env.storage().instance().set(&DataKey::Decimals, &decimals);
+        env.storage().instance().set(&DataKey::TotalSupply, &0i128);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        
+        // Flags opcionales (solo se guardan si están activos)
+        if options.community_pool {
+            env.storage().instance().set(&DataKey::CommunityPoolEnabled, &true);
+        }
+        if options.track_tx_count {
+            env.storage().instance().set(&DataKey::TxCountEnabled, &true);
+        }
+        if let Some(namespace) = &options.event_namespace {
+            env.storage().instance().set(&DataKey::EventNamespace, namespace);
+        }
+        if let Some(reserve) = &options.reserve {
+            env.storage().instance().set(&DataKeyExt::Reserve, reserve);
+        }
+        if let Some(guardian) = &options.guardian {
+            env.storage().instance().set(&DataKeyExt::Guardian, guardian);
+        }
+        if options.event_seq {
+            env.storage().instance().set(&DataKeyExt::EventSeqEnabled, &true);
+        }
+        if options.sender_seq {
+            env.storage().instance().set(&DataKeyExt::SenderSeqEnabled, &true);
+        }
+        if options.drain_enabled {
+            env.storage().instance().set(&DataKeyExt::DrainEnabled, &true);
+        }
+        if options.track_counterparties {
+            env.storage().instance().set(&DataKeyExt::CounterpartiesEnabled, &true);
+        }
+        
+        // Activo por defecto en deploys nuevos (ver set_block_self_approve)
+        env.storage().instance().set(&DataKeyExt::BlockSelfApprove, &true);
+        env.storage().instance().set(&DataKeyExt2::DeployLedger, &env.ledger().sequence());
+        
+        // 5. Extender TTL del storage de instance (30 días)
+        env.storage().instance().extend_ttl(100_000, 200_000);
+        
+        // 6. Emitir evento rico con todos los metadatos
+        Self::publish(
+            &env,
+            (event_topic::INIT, admin.clone()),
+            TokenMetadata {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                decimals,
+            }
+        );
+        
+        Ok(())
+    }
+    
+    fn initialize_with_multisig(
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+        options: InitOptions,
+        multisig: Multisig
+    ) -> Result<(), TokenError> {
+        Self::validate_multisig(&multisig)?;
+        
+        // Un reintento idéntico es un no-op; con otro multisig falla
+        if env.storage().instance().has(&DataKey::Initialized) {
+            Self::initialize_with_options(env.clone(), admin, name, symbol, decimals, options)?;
+            if Self::multisig(env.clone()) != Some(multisig) {
+                return Err(TokenError::AlreadyInitialized);
+            }
+            return Ok(());
+        }
+        
+        Self::initialize_with_options(env.clone(), admin, name, symbol, decimals, options)?;
+        env.storage().instance().set(&DataKeyExt2::Signers, &multisig.signers);
+        env.storage().instance().set(&DataKeyExt2::Threshold, &multisig.threshold);
+        
+        Ok(())
+    }
+    
+    fn initialize_mirroring(
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        reference_token: Address
+    ) -> Result<(), TokenError> {
+        let decimals = match token::Client::new(&env, &reference_token).try_decimals() {
+            Ok(Ok(decimals)) => decimals,
+            _ => return Err(TokenError::InvalidConfig),
+        };
+        
+        // initialize() rechaza valores fuera de rango con InvalidDecimals
+        Self::initialize(env, admin, name, symbol, decimals)
+    }
+    
+    fn initialize_with_roles(
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+        minters: Vec<Address>,
+        pausers: Vec<Address>
+    ) -> Result<(), TokenError> {
+        Self::validate_role_members(&minters)?;
+        Self::validate_role_members(&pausers)?;
+        
+        // Un reintento idéntico no vuelve a otorgar nada; sin auth del
+        // admin nadie puede sumar roles reusando los mismos parámetros
+        if env.storage().instance().has(&DataKey::Initialized) {
+            Self::initialize(env.clone(), admin, name, symbol, decimals)?;
+            let granted = minters.iter().all(|m| Self::has_role(env.clone(), Role::Minter, m))
+                && pausers.iter().all(|p| Self::has_role(env.clone(), Role::Pauser, p));
+            if !granted {
+                return Err(TokenError::AlreadyInitialized);
+            }
+            return Ok(());
+        }
+        
+        Self::initialize(env.clone(), admin, name, symbol, decimals)?;
+        
+        for minter in minters.iter() {
+            Self::set_role(&env, Role::Minter, &minter, true);
+        }
+        for pauser in pausers.iter() {
+            Self::set_role(&env, Role::Pauser, &pauser, true);
+        }
+        
+        Ok(())
+    }
+    
+    fn mint(env: Env, to: Address, amount: i128) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_MINT)?;
+        
+        // 2. Solo el admin (o el mint controller, si existe) puede mintear
+        let minter = Self::require_minter(&env)?;
+        
+        Self::execute_mint(env, minter, to, amount)
+    }
+    
+    fn mint_by(env: Env, minter: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_MINT)?;
+        
+        minter.require_auth();
+        if !Self::has_role(env.clone(), Role::Minter, minter.clone()) {
+            return Err(TokenError::Unauthorized);
+        }
+        
+        Self::execute_mint(env, minter, to, amount)
+    }
+    
+    fn approve_no_expiry(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128
+    ) -> Result<(), TokenError> {
+        let result = Self::execute_approve(env.clone(), from.clone(), spender, amount, NO_EXPIRATION);
+        Self::report_failure(&env, event_topic::APPROVE, from, result)
+    }
+    
+    fn approve_with_data(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128,
+        expiration_ledger: u32,
+        data: Bytes
+    ) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_APPROVE)?;
+        
+        // 2. Verificar autorización del owner
+        from.require_auth();
+        
+        // 3. Validaciones
+        if amount < 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        if data.len() > MAX_DATA_LENGTH {
+            return Err(TokenError::DataTooLarge);
+        }
+        
+        // Una expiración pasada solo tiene sentido para revocar
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            return Err(TokenError::InvalidExpiration);
+        }
+        
+        Self::require_not_self_spender(&env, &spender, amount)?;
+        Self::check_allowance_cap(&env, &from, &spender, amount)?;
+        
+        // 4. Obtener allowance anterior para el evento
+        let old_allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        
+        // 5. Actualizar allowance (el payload no se guarda)
+        Self::write_allowance(&env, &from, &spender, amount, expiration_ledger)?;
+        Self::clear_single_use(&env, &from, &spender);
+        
+        // 6. Evento con el payload para correlación off-chain
+        Self::publish(
+            &env,
+            (event_topic::APPROVE_WITH_DATA, from, spender),
+            (old_allowance, amount, expiration_ledger, data)
+        );
+        
+        Ok(())
+    }
+    
+    fn approve_once(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128,
+        expiration_ledger: u32
+    ) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_APPROVE)?;
+        
+        // 2. Verificar autorización del owner
+        from.require_auth();
+        
+        // 3. Validaciones (0 no tiene sentido para un uso único)
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(TokenError::InvalidExpiration);
+        }
+        
+        Self::require_not_self_spender(&env, &spender, amount)?;
+        Self::check_allowance_cap(&env, &from, &spender, amount)?;
+        
+        // 4. Obtener allowance anterior para el evento
+        let old_allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        
+        // 5. Guardar allowance y marcarlo como de un solo uso
+        Self::write_allowance(&env, &from, &spender, amount, expiration_ledger)?;
+        let marker = DataKey::SingleUse(from.clone(), spender.clone());
+        env.storage().persistent().set(&marker, &true);
+        env.storage().persistent().extend_ttl(&marker, 100_000, 200_000);
+        
+        // 6. Emitir evento
+        Self::publish(
+            &env,
+            (event_topic::APPROVE_ONCE, from, spender),
+            (old_allowance, amount, expiration_ledger)
+        );
+        
+        Ok(())
+    }
+    
+    fn increase_allowance(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128
+    ) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_APPROVE)?;
+        
+        // 2. Verificar autorización del owner
+        from.require_auth();
+        
+        // 3. Validaciones
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        // 4. Calcular el nuevo allowance (expirado cuenta como 0)
+        let current = Self::read_allowance(&env, &from, &spender);
+        let new_allowance = current.amount.checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        Self::require_not_self_spender(&env, &spender, new_allowance)?;
+        Self::check_allowance_cap(&env, &from, &spender, new_allowance)?;
+        
+        // 5. Conservar la expiración de un allowance activo
+        let expiration_ledger = if current.amount > 0 {
+            current.expiration_ledger
+        } else {
+            NO_EXPIRATION
+        };
+        Self::write_allowance(&env, &from, &spender, new_allowance, expiration_ledger)?;
+        
+        // 6. Emitir evento con allowance anterior y nuevo
+        Self::publish(
+            &env,
+            (event_topic::INCREASE_ALLOWANCE, from, spender),
+            (current.amount, new_allowance)
+        );
+        
+        Ok(())
+    }
+    
+    fn set_allowance_cap(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        cap: Option<i128>
+    ) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        from.require_auth();
+        
+        let key = DataKeyExt::AllowanceCap(from.clone(), spender.clone());
+        match cap {
+            Some(value) if value < 0 => return Err(TokenError::InvalidAmount),
+            Some(value) => {
+                env.storage().persistent().set(&key, &(value, Self::decimals(env.clone())));
+                env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        
+        Self::publish(&env, (event_topic::ALLOWANCE_CAP, from, spender), cap);
+        
+        Ok(())
+    }
+    
+    fn allowance_cap(env: Env, from: Address, spender: Address) -> Option<i128> {
+        env.storage().persistent()
+            .get::<DataKeyExt, (i128, u32)>(&DataKeyExt::AllowanceCap(from, spender))
+            .map(|(cap, decimals)| Self::rescale(&env, cap, decimals))
+    }
+    
+    fn set_allowance_auto_extend(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        delta_ledgers: u32
+    ) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        from.require_auth();
+        
+        let key = DataKeyExt2::AutoExtendAllowance(from.clone(), spender.clone());
+        if delta_ledgers == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &delta_ledgers);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
+        
+        Self::publish(&env, (event_topic::AUTO_EXTEND, from, spender), delta_ledgers);
+        
+        Ok(())
+    }
+    
+    fn allowance_auto_extend(env: Env, from: Address, spender: Address) -> u32 {
+        env.storage().persistent()
+            .get(&DataKeyExt2::AutoExtendAllowance(from, spender))
+            .unwrap_or(0)
+    }
+    
+    fn open_pool(
+        env: Env,
+        from: Address,
+        spender: Address,
+        total: i128,
+        expiration_ledger: u32
+    ) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_APPROVE)?;
+        
+        from.require_auth();
+        
+        if total < 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let key = DataKeyExt::Pool(from.clone(), spender.clone());
+        if total == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            if expiration_ledger < env.ledger().sequence() {
+                return Err(TokenError::InvalidExpiration);
+            }
+            
+            let pool = SpendingPool {
+                total,
+                remaining: total,
+                expiration_ledger,
+                decimals: Self::decimals(env.clone()),
+            };
+            env.storage().persistent().set(&key, &pool);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::POOL_OPEN, from, spender),
+            (total, expiration_ledger)
+        );
+        
+        Ok(())
+    }
+    
+    fn pull(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_TRANSFER_FROM)?;
+        
+        spender.require_auth();
+        
+        Self::validate_transfer(&env, &from, &to, amount)?;
+        
+        // El pool cubre todo lo debitado, incluido un fee a cargo del sender
+        let key = DataKeyExt::Pool(from.clone(), spender.clone());
+        let remaining = Self::pool_remaining(env.clone(), from.clone(), spender.clone());
+        let plan = Self::plan_transfer(&env, &from, &to, amount)?;
+        if remaining < plan.debit {
+            return Err(TokenError::InsufficientAllowance);
+        }
+        
+        // Por encima del umbral queda retenida como en transfer()
+        let held = Self::exceeds_delay_threshold(&env, amount);
+        if held {
+            Self::hold_transfer(&env, &from, &to, amount, &plan)?;
+        } else {
+            Self::apply_transfer(&env, &from, &to, &plan);
+            Self::record_outgoing_transfer(&env, &from, amount)?;
+        }
+        
+        // Pool agotado: se elimina la key
+        let new_remaining = remaining
+            .checked_sub(plan.debit)
+            .ok_or(TokenError::OverflowError)?;
+        if new_remaining == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            // Se reescribe con los decimales actuales
+            let mut pool: SpendingPool = env.storage().persistent().get(&key).unwrap();
+            pool.total = Self::rescale(&env, pool.total, pool.decimals);
+            pool.remaining = new_remaining;
+            pool.decimals = Self::decimals(env.clone());
+            env.storage().persistent().set(&key, &pool);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
+        
+        // La retenida ya emitió pending (y emite settle al liquidarse)
+        if held {
+            return Ok(());
+        }
+        
+        let topics = (event_topic::POOL_PULL, spender, from.clone(), to.clone());
+        match Self::next_sender_seq(&env, &from)? {
+            Some(seq) => Self::publish(
+                &env,
+                topics,
+                (amount, plan.new_from_balance, plan.new_to_balance, new_remaining, seq)
+            ),
+            None => Self::publish(
+                &env,
+                topics,
+                (amount, plan.new_from_balance, plan.new_to_balance, new_remaining)
+            ),
+        }
+        
+        Ok(())
+    }
+    
+    fn pool_remaining(env: Env, from: Address, spender: Address) -> i128 {
+        let key = DataKeyExt::Pool(from, spender);
+        match env.storage().persistent().get::<DataKeyExt, SpendingPool>(&key) {
+            Some(pool) if pool.expiration_ledger >= env.ledger().sequence() => {
+                Self::rescale(&env, pool.remaining, pool.decimals)
+            }
+            _ => 0,
+        }
+    }
+    
+    fn is_single_use(env: Env, from: Address, spender: Address) -> bool {
+        env.storage().persistent().has(&DataKey::SingleUse(from, spender))
+    }
+    
+    fn set_metadata(env: Env, name: String, symbol: String) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        Self::validate_metadata(&name, &symbol, false)?;
+        
+        env.storage().instance().set(&DataKey::TokenName, &name);
+        env.storage().instance().set(&DataKey::TokenSymbol, &symbol);
+        
+        Self::publish(
+            &env,
+            (event_topic::METADATA,),
+            TokenMetadata {
+                name,
+                symbol,
+                decimals: Self::decimals(env.clone()),
+            }
+        );
+        
+        Ok(())
+    }
+    
+    fn total_supply(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKey::TotalSupply)
+            .unwrap_or(0)
+    }
+    
+    fn admin(env: Env) -> Result<Address, TokenError> {
+        env.storage().instance()
+            .get(&DataKey::Admin)
+            .ok_or(TokenError::NotInitialized)
+    }
+    
+    fn community_pool(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKey::CommunityPool)
+            .unwrap_or(0)
+    }
+    
+    fn set_max_allowances_per_owner(env: Env, max: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKey::MaxAllowancesPerOwner, &max);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "max_allowances_per_owner")),
+            max
+        );
+        
+        Ok(())
+    }
+    
+    fn max_allowances_per_owner(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKey::MaxAllowancesPerOwner)
+            .unwrap_or(0)
+    }
+    
+    fn allowance_count(env: Env, owner: Address) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::AllowanceCount(owner))
+            .unwrap_or(0)
+    }
+    
+    fn set_allowance_grace(env: Env, grace: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if grace == 0 {
+            env.storage().instance().remove(&DataKeyExt::AllowanceGrace);
+        } else {
+            env.storage().instance().set(&DataKeyExt::AllowanceGrace, &grace);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "allowance_grace")),
+            grace
+        );
+        
+        Ok(())
+    }
+    
+    fn allowance_grace(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt::AllowanceGrace)
+            .unwrap_or(0)
+    }
+    
+    fn derived_address(env: Env, user_id: u64) -> Address {
+        let mut salt = [0u8; 32];
+        salt[24..].copy_from_slice(&user_id.to_be_bytes());
+        
+        env.deployer()
+            .with_address(
+                env.current_contract_address(),
+                BytesN::from_array(&env, &salt)
+            )
+            .deployed_address()
+    }
+    
+    fn mint_to_derived(env: Env, user_id: u64, amount: i128) -> Result<(), TokenError> {
+        let to = Self::derived_address(env.clone(), user_id);
+        Self::mint(env, to, amount)
+    }
+    
+    fn set_xlm_fee(
+        env: Env,
+        native_token: Address,
+        collector: Address,
+        fee: i128
+    ) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        let mut config = Self::current_config(&env)?;
+        config.xlm_fee = fee;
+        config.xlm_fee_collector = Some(collector.clone());
+        Self::validate_config(&env, &config)?;
+        
+        env.storage().instance().set(&DataKey::NativeToken, &native_token);
+        env.storage().instance().set(&DataKey::XlmFeeCollector, &collector);
+        env.storage().instance().set(&DataKey::XlmFee, &fee);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "xlm_fee")),
+            (native_token, collector, fee)
+        );
+        
+        Ok(())
+    }
+    
+    fn xlm_fee(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKey::XlmFee)
+            .unwrap_or(0)
+    }
+    
+    fn xlm_fee_collector(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::XlmFeeCollector)
+    }
+    
+    fn supply_at_ledger(env: Env, ledger: u32) -> i128 {
+        let (start, end): (u32, u32) = env.storage().instance()
+            .get(&DataKey::SupplyCheckpointRange)
+            .unwrap_or((0, 0));
+        
+        // Último checkpoint con checkpoint_ledger <= ledger
+        let mut low = start;
+        let mut high = end;
+        let mut supply = 0;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (checkpoint_ledger, checkpoint_supply): (u32, i128) = env.storage().persistent()
+                .get(&DataKey::SupplyCheckpoint(mid))
+                .expect("Supply checkpoint missing");
+            if checkpoint_ledger <= ledger {
+                supply = checkpoint_supply;
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        
+        supply
+    }
+    
+    fn set_transfer_fee(env: Env, bps: u32, treasury: Address) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        Self::write_transfer_fee(&env, bps, treasury)
+    }
+    
+    fn transfer_fee_bps(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKey::TransferFeeBps)
+            .unwrap_or(0)
+    }
+    
+    fn set_flat_fee(env: Env, amount: i128) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        Self::require_whole_units(&env, amount)?;
+        
+        let mut config = Self::current_config(&env)?;
+        config.flat_fee = amount;
+        Self::validate_config(&env, &config)?;
+        
+        if amount == 0 {
+            env.storage().instance().remove(&DataKeyExt::FlatFee);
+        } else {
+            env.storage().instance().set(&DataKeyExt::FlatFee, &amount);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "flat_fee")),
+            amount
+        );
+        
+        Ok(())
+    }
+    
+    fn flat_fee(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKeyExt::FlatFee)
+            .unwrap_or(0)
+    }
+    
+    fn treasury(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Treasury)
+    }
+    
+    fn set_fee_recipients(env: Env, recipients: Vec<(Address, u32)>) -> Result<(), TokenError> {
+        let admin = Self::require_config_admin(&env)?;
+        
+        if recipients.len() > MAX_FEE_RECIPIENTS {
+            return Err(TokenError::BatchTooLarge);
+        }
+        
+        // Partes positivas que suman 100%, sin receptores repetidos ni
+        // loops de fees (admin o el propio contrato)
+        let this = env.current_contract_address();
+        let mut total: u32 = 0;
+        for (index, (recipient, bps)) in recipients.iter().enumerate() {
+            if bps == 0 || recipient == admin || recipient == this {
+                return Err(TokenError::InvalidConfig);
+            }
+            if recipients.iter().skip(index + 1).any(|(other, _)| other == recipient) {
+                return Err(TokenError::InvalidConfig);
+            }
+            total = total.checked_add(bps).ok_or(TokenError::InvalidConfig)?;
+        }
+        if !recipients.is_empty() && total != BPS_DENOMINATOR {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        if recipients.is_empty() {
+            env.storage().instance().remove(&DataKeyExt::FeeConfig);
+        } else {
+            env.storage().instance().set(&DataKeyExt::FeeConfig, &recipients);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "fee_recipients")),
+            recipients
+        );
+        
+        Ok(())
+    }
+    
+    fn fee_recipients(env: Env) -> Vec<(Address, u32)> {
+        env.storage().instance()
+            .get(&DataKeyExt::FeeConfig)
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    fn set_rounding_mode(env: Env, mode: RoundingMode) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKey::RoundingMode, &mode);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "rounding_mode")),
+            mode
+        );
+        
+        Ok(())
+    }
+    
+    fn rounding_mode(env: Env) -> RoundingMode {
+        env.storage().instance()
+            .get(&DataKey::RoundingMode)
+            .unwrap_or(RoundingMode::Floor)
+    }
+    
+    fn set_fee_payer(env: Env, payer: FeePayer) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKey::FeePayer, &payer);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "fee_payer")),
+            payer
+        );
+        
+        Ok(())
+    }
+    
+    fn fee_payer(env: Env) -> FeePayer {
+        env.storage().instance()
+            .get(&DataKey::FeePayer)
+            .unwrap_or(FeePayer::Recipient)
+    }
+    
+    fn simulate_transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<(i128, i128, i128), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_TRANSFER)?;
+        
+        Self::validate_transfer(&env, &from, &to, amount)?;
+        let plan = Self::plan_transfer(&env, &from, &to, amount)?;
+        Self::check_xlm_fee(&env, &from)?;
+        
+        Ok((plan.new_from_balance, plan.new_to_balance, plan.fee))
+    }
+    
+    fn check_transfer_auth(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_TRANSFER)?;
+        
+        from.require_auth();
+        
+        // Mismos chequeos que execute_transfer, sin aplicar el plan
+        Self::validate_transfer(&env, &from, &to, amount)?;
+        Self::plan_transfer(&env, &from, &to, amount)?;
+        Self::check_xlm_fee(&env, &from)?;
+        
+        Ok(())
+    }
+    
+    fn freeze(env: Env, account: Address) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        Self::set_frozen(&env, &account, true);
+        Ok(())
+    }
+    
+    fn unfreeze(env: Env, account: Address) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        Self::set_frozen(&env, &account, false);
+        Ok(())
+    }
+    
+    fn freeze_batch(env: Env, accounts: Vec<Address>) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        
+        Self::validate_batch_len(accounts.len())?;
+        
+        for account in accounts.iter() {
+            Self::set_frozen(&env, &account, true);
+        }
+        
+        Ok(())
+    }
+    
+    fn unfreeze_batch(env: Env, accounts: Vec<Address>) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        
+        Self::validate_batch_len(accounts.len())?;
+        
+        for account in accounts.iter() {
+            Self::set_frozen(&env, &account, false);
+        }
+        
+        Ok(())
+    }
+    
+    fn set_protected(env: Env, account: Address, protected: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        let key = DataKeyExt::Protected(account.clone());
+        if protected {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        
+        Self::publish(&env, (event_topic::PROTECT, account), protected);
+        
+        Ok(())
+    }
+    
+    fn is_protected(env: Env, account: Address) -> bool {
+        env.storage().persistent().has(&DataKeyExt::Protected(account))
+    }
+    
+    fn set_launch_guard(env: Env, until_ledger: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if until_ledger == 0 {
+            env.storage().instance().remove(&DataKeyExt::LaunchGuardUntil);
+        } else {
+            env.storage().instance().set(&DataKeyExt::LaunchGuardUntil, &until_ledger);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "launch_guard")),
+            until_ledger
+        );
+        
+        Ok(())
+    }
+    
+    fn launch_guard_until(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt::LaunchGuardUntil)
+            .unwrap_or(0)
+    }
+    
+    fn set_launch_router(env: Env, router: Address, allowed: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        let key = DataKeyExt::LaunchRouter(router.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        
+        Self::publish(&env, (event_topic::LAUNCH_ROUTER, router), allowed);
+        
+        Ok(())
+    }
+    
+    fn is_launch_router(env: Env, router: Address) -> bool {
+        env.storage().persistent().has(&DataKeyExt::LaunchRouter(router))
+    }
+    
+    fn is_frozen(env: Env, account: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Frozen(account))
+    }
+    
+    fn tx_count(env: Env, account: Address) -> u64 {
+        env.storage().persistent()
+            .get(&DataKey::TxCount(account))
+            .unwrap_or(0)
+    }
+    
+    fn sender_seq(env: Env, account: Address) -> u64 {
+        env.storage().persistent()
+            .get(&DataKeyExt::SenderSeq(account))
+            .unwrap_or(0)
+    }
+    
+    fn last_counterparty(env: Env, account: Address) -> LastCounterparty {
+        env.storage().persistent()
+            .get(&DataKeyExt::LastCounterparty(account))
+            .unwrap_or(LastCounterparty {
+                sent_to: None,
+                received_from: None,
+            })
+    }
+    
+    fn balances(env: Env, accounts: Vec<Address>) -> Result<Vec<i128>, TokenError> {
+        Self::validate_batch_len(accounts.len())?;
+        
+        let mut result = Vec::new(&env);
+        for account in accounts.iter() {
+            result.push_back(Self::balance(env.clone(), account));
+        }
+        
+        Ok(result)
+    }
+    
+    fn allowances(env: Env, from: Address, spenders: Vec<Address>) -> Result<Vec<i128>, TokenError> {
+        Self::validate_batch_len(spenders.len())?;
+        
+        let mut result = Vec::new(&env);
+        for spender in spenders.iter() {
+            result.push_back(Self::read_allowance(&env, &from, &spender).amount);
+        }
+        
+        Ok(result)
+    }
+    
+    fn holders(env: Env, start: u32, limit: u32) -> Result<Vec<Address>, TokenError> {
+        Self::validate_batch_len(limit)?;
+        
+        let end = Self::holder_count(env.clone()).min(start.saturating_add(limit));
+        let mut result = Vec::new(&env);
+        for index in start..end {
+            if let Some(holder) = env.storage().persistent().get(&DataKey::Holder(index)) {
+                result.push_back(holder);
+            }
+        }
+        
+        Ok(result)
+    }
+    
+    fn holder_count(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKey::HolderCount)
+            .unwrap_or(0)
+    }
+    
+    fn set_max_holders(env: Env, max: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKeyExt::MaxHolders, &max);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "max_holders")),
+            max
+        );
+        
+        Ok(())
+    }
+    
+    fn max_holders(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt::MaxHolders)
+            .unwrap_or(0)
+    }
+    
+    fn self_balance(env: Env) -> i128 {
+        Self::balance(env.clone(), env.current_contract_address())
+    }
+    
+    fn foreign_balance(env: Env, token: Address) -> i128 {
+        token::Client::new(&env, &token).balance(&env.current_contract_address())
+    }
+    
+    fn pause(env: Env, caller: Address) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        // Frenar lo puede el admin, el guardian o un Pauser; despausar solo el admin.
+        // Con multisig, el admin pausa y despausa solo vía propuesta
+        caller.require_auth();
+        let is_admin = caller == Self::admin(env.clone())? && Self::multisig(env.clone()).is_none();
+        if !is_admin
+            && Self::guardian(env.clone()).as_ref() != Some(&caller)
+            && !Self::has_role(env.clone(), Role::Pauser, caller.clone())
+        {
+            return Err(TokenError::Unauthorized);
+        }
+        
+        env.storage().instance().set(&DataKey::Paused, &true);
+        
+        Self::publish(
+            &env,
+            (event_topic::PAUSE, caller),
+            Self::pause_policy(env.clone())
+        );
+        
+        Ok(())
+    }
+    
+    fn unpause(env: Env) -> Result<(), TokenError> {
+        let admin = Self::require_admin(&env)?;
+        
+        env.storage().instance().remove(&DataKey::Paused);
+        
+        Self::publish(&env, (event_topic::UNPAUSE, admin), ());
+        
+        Ok(())
+    }
+    
+    fn grant_role(env: Env, role: Role, account: Address) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        Self::set_role(&env, role, &account, true);
+        Ok(())
+    }
+    
+    fn revoke_role(env: Env, role: Role, account: Address) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        Self::set_role(&env, role, &account, false);
+        Ok(())
+    }
+    
+    fn has_role(env: Env, role: Role, account: Address) -> bool {
+        env.storage().persistent().has(&DataKeyExt::RoleMember(role, account))
+    }
+    
+    fn set_guardian(env: Env, guardian: Option<Address>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        match &guardian {
+            Some(guardian) => env.storage().instance().set(&DataKeyExt::Guardian, guardian),
+            None => env.storage().instance().remove(&DataKeyExt::Guardian),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "guardian")),
+            guardian
+        );
+        
+        Ok(())
+    }
+    
+    fn permissions(env: Env, account: Address) -> Permissions {
+        let admin = match Self::admin(env.clone()) {
+            Ok(admin) => admin,
+            Err(_) => return Permissions {
+                can_mint: false,
+                can_pause: false,
+                can_freeze: false,
+                is_admin: false,
+            },
+        };
+        // Con multisig el admin no actúa solo (ver require_admin)
+        let is_admin = account == admin && Self::multisig(env.clone()).is_none();
+        
+        // Mismas reglas que mint()/mint_by() y pause()
+        let can_mint = match Self::mint_controller(env.clone()) {
+            Some(controller) => account == controller,
+            None => is_admin,
+        } || Self::has_role(env.clone(), Role::Minter, account.clone());
+        let can_pause = is_admin
+            || Self::guardian(env.clone()).as_ref() == Some(&account)
+            || Self::has_role(env.clone(), Role::Pauser, account.clone());
+        
+        Permissions {
+            can_mint,
+            can_pause,
+            can_freeze: is_admin,
+            is_admin,
+        }
+    }
+    
+    fn account_storage_info(env: Env, account: Address) -> AccountStorage {
+        let storage = env.storage().persistent();
+        
+        AccountStorage {
+            has_balance: storage.has(&DataKey::Balance(account.clone())),
+            has_locked: storage.has(&DataKey::Locked(account.clone())),
+            allowance_count: Self::allowance_count(env.clone(), account.clone()),
+            is_frozen: storage.has(&DataKey::Frozen(account)),
+        }
+    }
+    
+    fn guardian(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKeyExt::Guardian)
+    }
+    
+    fn is_paused(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Paused)
+    }
+    
+    fn set_pause_policy(env: Env, mask: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        // Bits desconocidos probablemente son un error del caller
+        if mask & !PAUSE_ALL != 0 {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        env.storage().instance().set(&DataKey::PausePolicy, &mask);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "pause_policy")),
+            mask
+        );
+        
+        Ok(())
+    }
+    
+    fn pause_policy(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKey::PausePolicy)
+            .unwrap_or(PAUSE_ALL)
+    }
+    
+    fn allowance_display(env: Env, from: Address, spender: Address) -> (i128, u64) {
+        let amount = Self::allowance(env.clone(), from, spender);
+        Self::split_decimals(&env, amount)
+    }
+    
+    fn set_mint_controller(env: Env, controller: Option<Address>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        match &controller {
+            Some(controller) => env.storage().instance().set(&DataKey::MintController, controller),
+            None => env.storage().instance().remove(&DataKey::MintController),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "mint_controller")),
+            controller
+        );
+        
+        Ok(())
+    }
+    
+    fn mint_controller(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::MintController)
+    }
+    
+    fn revoke_allowances(env: Env, from: Address, spenders: Vec<Address>) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        
+        Self::validate_batch_len(spenders.len())?;
+        
+        for spender in spenders.iter() {
+            let old_allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+            Self::write_allowance(&env, &from, &spender, 0, 0)?;
+            Self::clear_single_use(&env, &from, &spender);
+            
+            Self::publish(
+                &env,
+                (event_topic::REVOKE, from.clone(), spender),
+                old_allowance
+            );
+        }
+        
+        Ok(())
+    }
+    
+    fn health(env: Env) -> HealthReport {
+        HealthReport {
+            initialized: env.storage().instance().has(&DataKey::Initialized),
+            paused: Self::is_paused(env.clone()),
+            total_supply: Self::total_supply(env.clone()),
+            admin_set: env.storage().instance().has(&DataKey::Admin),
+            version: CONTRACT_VERSION,
+        }
+    }
+    
+    fn config(env: Env) -> TokenConfig {
+        let options = Self::stored_options(&env);
+        TokenConfig {
+            admin: env.storage().instance().get(&DataKey::Admin),
+            community_pool_enabled: options.community_pool,
+            track_tx_count: options.track_tx_count,
+            event_namespace: options.event_namespace,
+            event_seq: options.event_seq,
+            sender_seq: options.sender_seq,
+            reserve: options.reserve,
+            paused: Self::is_paused(env.clone()),
+            pause_policy: Self::pause_policy(env.clone()),
+            guardian: options.guardian,
+            mint_controller: Self::mint_controller(env.clone()),
+            allow_mint_to_frozen: Self::allow_mint_to_frozen(env.clone()),
+            max_allowances_per_owner: Self::max_allowances_per_owner(env.clone()),
+            allowance_grace: Self::allowance_grace(env.clone()),
+            require_spender_opt_in: Self::require_spender_opt_in(env.clone()),
+            transfer_fee_bps: Self::transfer_fee_bps(env.clone()),
+            flat_fee: Self::flat_fee(env.clone()),
+            treasury: Self::treasury(env.clone()),
+            fee_recipients: Self::fee_recipients(env.clone()),
+            rounding_mode: Self::rounding_mode(env.clone()),
+            fee_payer: Self::fee_payer(env.clone()),
+            native_token: env.storage().instance().get(&DataKey::NativeToken),
+            xlm_fee: Self::xlm_fee(env.clone()),
+            xlm_fee_collector: Self::xlm_fee_collector(env.clone()),
+            min_account_balance: Self::min_account_balance(env.clone()),
+            max_holders: Self::max_holders(env.clone()),
+            action_cooldown: Self::action_cooldown(env.clone()),
+            legacy_token: Self::legacy_token(env.clone()),
+            migration_rate_bps: Self::migration_rate(env.clone()),
+            max_supply: Self::max_supply(env.clone()),
+            inflation_rate_bps: Self::inflation(env.clone())
+                .map(|config| config.rate_bps)
+                .unwrap_or(0),
+            buyback_executor: Self::buyback_executor(env.clone()),
+            notification_relay: Self::notification_relay(env.clone()),
+            default_volume_limit: Self::default_volume_limit(env.clone()),
+            volume_window: Self::volume_window(env.clone()),
+            launch_guard_until: Self::launch_guard_until(env.clone()),
+            near_expiry_warnings: Self::near_expiry_warnings(env.clone()),
+            emit_failure_events: Self::emit_failure_events(env.clone()),
+            suppress_noop_events: Self::suppress_noop_events(env.clone()),
+            require_non_empty: Self::require_non_empty(env),
+        }
+    }
+    
+    fn set_allow_mint_to_frozen(env: Env, allow: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKey::AllowMintToFrozen, &allow);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "allow_mint_to_frozen")),
+            allow
+        );
+        
+        Ok(())
+    }
+    
+    fn allow_mint_to_frozen(env: Env) -> bool {
+        env.storage().instance()
+            .get(&DataKey::AllowMintToFrozen)
+            .unwrap_or(false)
+    }
+    
+    fn transfer_idempotent(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+        op_id: BytesN<32>
+    ) -> Result<(), TokenError> {
+        // El op_id es por sender: otra cuenta no puede "gastar" el de `from`
+        let key = DataKey::ProcessedOp(from.clone(), op_id);
+        if env.storage().temporary().has(&key) {
+            // Reintento: ya se procesó, no se mueve nada
+            from.require_auth();
+            return Ok(());
+        }
+        
+        Self::transfer(env.clone(), from, to, amount)?;
+        
+        env.storage().temporary().set(&key, &true);
+        env.storage().temporary().extend_ttl(&key, OP_ID_TTL, OP_ID_TTL);
+        
+        Ok(())
+    }
+    
+    fn transfer_from_tracked(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+        ref_id: BytesN<32>
+    ) -> Result<(), TokenError> {
+        let key = DataKeyExt::PaymentRef(ref_id.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(TokenError::InvalidPaymentRef);
+        }
+        
+        // Lo recibido por `to` (neto de fee) es lo que se reembolsa
+        let to_balance = Self::balance(env.clone(), to.clone());
+        Self::transfer_from(env.clone(), spender, from.clone(), to.clone(), amount)?;
+        let received = Self::balance(env.clone(), to.clone()) - to_balance;
+        
+        let payment = PaymentRecord {
+            from,
+            to,
+            amount: received,
+            refunded: false,
+            decimals: Self::decimals(env.clone()),
+        };
+        env.storage().persistent().set(&key, &payment);
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        
+        Self::publish(
+            &env,
+            (event_topic::PAYMENT_REF, ref_id),
+            (payment.from, payment.to, payment.amount)
+        );
+        
+        Ok(())
+    }
+    
+    fn refund(env: Env, caller: Address, ref_id: BytesN<32>) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_TRANSFER)?;
+        
+        // 2. Solo el receptor del pago o el admin (sin multisig) pueden reembolsar
+        caller.require_auth();
+        let key = DataKeyExt::PaymentRef(ref_id.clone());
+        let mut payment: PaymentRecord = env.storage().persistent()
+            .get(&key)
+            .ok_or(TokenError::InvalidPaymentRef)?;
+        let is_admin = caller == Self::admin(env.clone())? && Self::multisig(env.clone()).is_none();
+        if caller != payment.to && !is_admin {
+            return Err(TokenError::Unauthorized);
+        }
+        
+        // 3. Validaciones
+        if payment.refunded {
+            return Err(TokenError::AlreadyRefunded);
+        }
+        
+        Self::require_not_frozen(&env, &payment.to)?;
+        
+        // Registrado antes de migrate_decimals: llevarlo a los decimales actuales
+        let amount = 10i128.pow(Self::decimals(env.clone()) - payment.decimals)
+            .checked_mul(payment.amount)
+            .ok_or(TokenError::OverflowError)?;
+        if Self::spendable_balance(env.clone(), payment.to.clone()) < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        // 4. Devolver los tokens (sin fee)
+        let new_to_balance = Self::balance(env.clone(), payment.to.clone())
+            .checked_sub(amount)
+            .ok_or(TokenError::OverflowError)?;
+        let new_from_balance = Self::balance(env.clone(), payment.from.clone())
+            .checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        Self::write_balance(&env, &payment.to, new_to_balance);
+        Self::write_balance(&env, &payment.from, new_from_balance);
+        Self::record_receipt(&env, &payment.from);
+        
+        // 5. Marcar como reembolsado (el registro se conserva)
+        payment.refunded = true;
+        env.storage().persistent().set(&key, &payment);
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        
+        // 6. Emitir evento
+        Self::publish(
+            &env,
+            (event_topic::REFUND, payment.to, payment.from),
+            (amount, ref_id)
+        );
+        
+        Ok(())
+    }
+    
+    fn payment(env: Env, ref_id: BytesN<32>) -> Option<PaymentRecord> {
+        env.storage().persistent().get(&DataKeyExt::PaymentRef(ref_id))
+    }
+    
+    fn transfer_and_notify(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<(), TokenError> {
+        // Una transferencia retenida no le acredita nada al contrato
+        // todavía (y el sender la puede cancelar): no hay qué avisar
+        if Self::is_contract(&to) && Self::exceeds_delay_threshold(&env, amount) {
+            return Err(TokenError::NotificationFailed);
+        }
+        
+        Self::transfer(env.clone(), from.clone(), to.clone(), amount)?;
+        
+        if Self::is_contract(&to) {
+            let notified = env.try_invoke_contract::<(), soroban_sdk::Error>(
+                &to,
+                &Symbol::new(&env, "tokens_received"),
+                (from, amount).into_val(&env)
+            );
+            if !matches!(notified, Ok(Ok(()))) {
+                return Err(TokenError::NotificationFailed);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    fn snapshot(env: Env) -> Result<u64, TokenError> {
+        Self::require_admin(&env)?;
+        
+        let id = Self::current_snapshot_id(env.clone())
+            .checked_add(1)
+            .ok_or(TokenError::OverflowError)?;
+        env.storage().instance().set(&DataKey::SnapshotId, &id);
+        
+        Self::publish(
+            &env,
+            (event_topic::SNAPSHOT,),
+            (id, Self::total_supply(env.clone()))
+        );
+        
+        Ok(id)
+    }
+    
+    fn current_snapshot_id(env: Env) -> u64 {
+        env.storage().instance()
+            .get(&DataKey::SnapshotId)
+            .unwrap_or(0)
+    }
+    
+    fn balance_at_snapshot(
+        env: Env,
+        account: Address,
+        snapshot_id: u64
+    ) -> Result<i128, TokenError> {
+        if snapshot_id == 0 || snapshot_id > Self::current_snapshot_id(env.clone()) {
+            return Err(TokenError::InvalidSnapshot);
+        }
+        
+        // El primer checkpoint con id >= snapshot_id guarda el balance que
+        // la cuenta tenía al declararse snapshot_id (no cambió entre medio)
+        let ids: Vec<u64> = env.storage().persistent()
+            .get(&DataKey::BalanceCheckpointIds(account.clone()))
+            .unwrap_or(Vec::new(&env));
+        let index = match ids.binary_search(snapshot_id) {
+            Ok(index) | Err(index) => index,
+        };
+        
+        match ids.get(index) {
+            Some(id) => Ok(env.storage().persistent()
+                .get(&DataKey::LastBalanceCheckpoint(account, id))
+                .unwrap_or(0)),
+            // Sin cambios desde el snapshot: el balance actual es el vigente
+            None => Ok(Self::balance(env, account)),
+        }
+    }
+    
+    fn set_require_spender_opt_in(env: Env, required: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKey::RequireSpenderOptIn, &required);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "require_spender_opt_in")),
+            required
+        );
+        
+        Ok(())
+    }
+    
+    fn require_spender_opt_in(env: Env) -> bool {
+        env.storage().instance()
+            .get(&DataKey::RequireSpenderOptIn)
+            .unwrap_or(false)
+    }
+    
+    fn accept_spending(env: Env, spender: Address, from: Address) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        spender.require_auth();
+        
+        let key = DataKey::SpenderOptIn(spender.clone(), from.clone());
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        
+        Self::publish(&env, (event_topic::SPENDER_OPT_IN, spender, from), true);
+        
+        Ok(())
+    }
+    
+    fn has_accepted_spending(env: Env, spender: Address, from: Address) -> bool {
+        env.storage().persistent().has(&DataKey::SpenderOptIn(spender, from))
+    }
+    
+    fn set_restrict_destinations(env: Env, owner: Address, enabled: bool) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        owner.require_auth();
+        
+        let key = DataKeyExt::RestrictDestinations(owner.clone());
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        
+        Self::publish(&env, (event_topic::RESTRICT_DESTINATIONS, owner), enabled);
+        
+        Ok(())
+    }
+    
+    fn restricts_destinations(env: Env, owner: Address) -> bool {
+        env.storage().persistent().has(&DataKeyExt::RestrictDestinations(owner))
+    }
+    
+    fn approve_destination(env: Env, owner: Address, destination: Address) -> Result<(), TokenError> {
+        Self::set_destination(&env, owner, destination, true)
+    }
+    
+    fn revoke_destination(env: Env, owner: Address, destination: Address) -> Result<(), TokenError> {
+        Self::set_destination(&env, owner, destination, false)
+    }
+    
+    fn is_destination_approved(env: Env, owner: Address, destination: Address) -> bool {
+        env.storage().persistent().has(&DataKeyExt::ApprovedDest(owner, destination))
+    }
+    
+    fn set_emit_failure_events(env: Env, enabled: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKey::EmitFailureEvents, &enabled);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "emit_failure_events")),
+            enabled
+        );
+        
+        Ok(())
+    }
+    
+    fn emit_failure_events(env: Env) -> bool {
+        env.storage().instance()
+            .get(&DataKey::EmitFailureEvents)
+            .unwrap_or(false)
+    }
+    
+    fn set_suppress_noop_events(env: Env, enabled: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKeyExt::SuppressNoopEvents, &enabled);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "suppress_noop_events")),
+            enabled
+        );
+        
+        Ok(())
+    }
+    
+    fn suppress_noop_events(env: Env) -> bool {
+        env.storage().instance()
+            .get(&DataKeyExt::SuppressNoopEvents)
+            .unwrap_or(false)
+    }
+    
+    fn swap(
+        env: Env,
+        a: Address,
+        b: Address,
+        amount_a_to_b: i128,
+        amount_b_to_a: i128
+    ) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_TRANSFER)?;
+        
+        a.require_auth();
+        b.require_auth();
+        
+        if amount_a_to_b < 0 || amount_b_to_a < 0 || (amount_a_to_b == 0 && amount_b_to_a == 0) {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        if a == b {
+            return Err(TokenError::InvalidRecipient);
+        }
+        
+        Self::require_not_frozen(&env, &a)?;
+        Self::require_not_frozen(&env, &b)?;
+        
+        // Una pata retenida rompería la atomicidad del intercambio
+        if Self::exceeds_delay_threshold(&env, amount_a_to_b)
+            || Self::exceeds_delay_threshold(&env, amount_b_to_a)
+        {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        // Ambas patas se planifican antes de mover nada: cada lado paga su
+        // debit (fee incluido) con su propio saldo y `b` no puede pagar
+        // con lo que recibe de `a`
+        let legs = [(&a, &b, amount_a_to_b), (&b, &a, amount_b_to_a)];
+        for (from, to, amount) in legs {
+            if amount > 0 {
+                // Cada lado pasa por las mismas reglas que un transfer()
+                Self::validate_transfer(&env, from, to, amount)?;
+                let plan = Self::plan_transfer(&env, from, to, amount)?;
+                if plan.debit > Self::spendable_balance(env.clone(), from.clone()) {
+                    return Err(TokenError::InsufficientBalance);
+                }
+            }
+        }
+        
+        // La segunda pata se recalcula sobre los balances que dejó la primera
+        for (from, to, amount) in legs {
+            if amount > 0 {
+                let plan = Self::plan_transfer(&env, from, to, amount)?;
+                Self::apply_transfer(&env, from, to, &plan);
+                Self::record_outgoing_transfer(&env, from, amount)?;
+            }
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::SWAP, a.clone(), b.clone()),
+            (
+                amount_a_to_b,
+                amount_b_to_a,
+                Self::balance(env.clone(), a),
+                Self::balance(env.clone(), b)
+            )
+        );
+        
+        Ok(())
+    }
+    
+    fn set_min_account_balance(env: Env, min: i128) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if min < 0 {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        env.storage().instance().set(&DataKey::MinAccountBalance, &min);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "min_account_balance")),
+            min
+        );
+        
+        Ok(())
+    }
+    
+    fn min_account_balance(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKey::MinAccountBalance)
+            .unwrap_or(0)
+    }
+    
+    fn set_action_cooldown(env: Env, ledgers: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKey::ActionCooldown, &ledgers);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "action_cooldown")),
+            ledgers
+        );
+        
+        Ok(())
+    }
+    
+    fn action_cooldown(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKey::ActionCooldown)
+            .unwrap_or(0)
+    }
+    
+    fn enable_decimals_migration(env: Env) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if Self::decimals_migration_status(env.clone()) == MigrationStatus::Disabled {
+            env.storage().instance().set(&DataKey::DecimalsMigration, &MigrationStatus::Enabled);
+        }
+        
+        Ok(())
+    }
+    
+    fn decimals_migration_status(env: Env) -> MigrationStatus {
+        env.storage().instance()
+            .get(&DataKey::DecimalsMigration)
+            .unwrap_or(MigrationStatus::Disabled)
+    }
+    
+    fn migrate_decimals(env: Env, new_decimals: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if Self::decimals_migration_status(env.clone()) != MigrationStatus::Enabled {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        // Tasas por token que cambiarían de valor: hay que quitarlas antes.
+        // Los montos por par de cuentas se reescalan al leerlos (ver rescale)
+        if Self::redemption_asset(env.clone()).is_some()
+            || Self::legacy_token(env.clone()).is_some()
+        {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        let old_decimals = Self::decimals(env.clone());
+        if new_decimals <= old_decimals || new_decimals > MAX_DECIMALS {
+            return Err(TokenError::InvalidDecimals);
+        }
+        let factor = 10i128.pow(new_decimals - old_decimals);
+        let scale = |amount: i128| amount.checked_mul(factor).ok_or(TokenError::OverflowError);
+        
+        // Balances, bloqueados, vestings, snapshots, rewards y volumen de
+        // todos los holders registrados
+        let window = Self::current_volume_window(&env);
+        for index in 0..Self::holder_count(env.clone()) {
+            let holder: Address = env.storage().persistent()
+                .get(&DataKey::Holder(index))
+                .expect("Holder missing");
+            
+            let balance = Self::balance(env.clone(), holder.clone());
+            if balance > 0 {
+                Self::write_balance(&env, &holder, scale(balance)?);
+            }
+            
+            let locked = Self::locked_balance(env.clone(), holder.clone());
+            if locked > 0 {
+                Self::write_locked(&env, &holder, scale(locked)?);
+            }
+            
+            let schedule = Self::vest_schedule(env.clone(), holder.clone());
+            if !schedule.is_empty() {
+                let mut scaled = Vec::new(&env);
+                for tranche in schedule.iter() {
+                    scaled.push_back(VestTranche {
+                        amount: scale(tranche.amount)?,
+                        unlock_ledger: tranche.unlock_ledger,
+                    });
+                }
+                Self::write_vest_schedule(&env, &holder, &scaled);
+            }
+            
+            // Después de write_balance, que pudo agregar un checkpoint o rewards
+            let ids: Vec<u64> = env.storage().persistent()
+                .get(&DataKey::BalanceCheckpointIds(holder.clone()))
+                .unwrap_or(Vec::new(&env));
+            for id in ids.iter() {
+                let key = DataKey::LastBalanceCheckpoint(holder.clone(), id);
+                let checkpoint: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+                env.storage().persistent().set(&key, &scale(checkpoint)?);
+            }
+            
+            let key = DataKeyExt2::AccruedRewards(holder.clone());
+            if let Some(accrued) = env.storage().persistent().get::<DataKeyExt2, i128>(&key) {
+                env.storage().persistent().set(&key, &scale(accrued)?);
+            }
+            
+            let key = DataKeyExt::VolumeUsed(holder.clone(), window);
+            if let Some(used) = env.storage().temporary().get::<DataKeyExt, i128>(&key) {
+                env.storage().temporary().set(&key, &scale(used)?);
+            }
+        }
+        
+        let new_total = scale(Self::total_supply(env.clone()))?;
+        env.storage().instance().set(&DataKey::TotalSupply, &new_total);
+        
+        // Historial de supply (antes de agregar el checkpoint de new_total)
+        let (start, end): (u32, u32) = env.storage().instance()
+            .get(&DataKey::SupplyCheckpointRange)
+            .unwrap_or((0, 0));
+        for index in start..end {
+            let key = DataKey::SupplyCheckpoint(index);
+            if let Some((ledger, supply)) = env.storage().persistent().get::<DataKey, (u32, i128)>(&key) {
+                env.storage().persistent().set(&key, &(ledger, scale(supply)?));
+            }
+        }
+        Self::record_supply_checkpoint(&env, new_total);
+        
+        if let Some(cap) = Self::max_supply(env.clone()) {
+            env.storage().instance().set(&DataKeyExt::MaxSupply, &scale(cap)?);
+        }
+        
+        if let Some(limit) = Self::default_volume_limit(env.clone()) {
+            env.storage().instance().set(&DataKeyExt::DefaultVolumeLimit, &scale(limit)?);
+        }
+        
+        // Montos de configuración y totales (ausentes si son 0)
+        let pool = Self::community_pool(env.clone());
+        if pool > 0 {
+            env.storage().instance().set(&DataKey::CommunityPool, &scale(pool)?);
+        }
+        
+        let min = Self::min_account_balance(env.clone());
+        if min > 0 {
+            env.storage().instance().set(&DataKey::MinAccountBalance, &scale(min)?);
+        }
+        
+        let flat_fee = Self::flat_fee(env.clone());
+        if flat_fee > 0 {
+            env.storage().instance().set(&DataKeyExt::FlatFee, &scale(flat_fee)?);
+        }
+        
+        let threshold = Self::delay_threshold(env.clone());
+        if threshold > 0 {
+            env.storage().instance().set(&DataKeyExt::DelayThreshold, &scale(threshold)?);
+        }
+        
+        let buyback = Self::total_buyback_burned(env.clone());
+        if buyback > 0 {
+            env.storage().instance().set(&DataKeyExt::TotalBuybackBurned, &scale(buyback)?);
+        }
+        
+        let redeemed = Self::total_redeemed(env.clone());
+        if redeemed > 0 {
+            env.storage().instance().set(&DataKeyExt::TotalRedeemed, &scale(redeemed)?);
+        }
+        
+        let penalties = Self::total_penalty_burned(env.clone());
+        if penalties > 0 {
+            env.storage().instance().set(&DataKeyExt2::TotalPenaltyBurned, &scale(penalties)?);
+        }
+        
+        // Transferencias retenidas todavía abiertas
+        let next_pending: u64 = env.storage().instance()
+            .get(&DataKeyExt::NextPendingId)
+            .unwrap_or(0);
+        for id in 0..next_pending {
+            if let Some(mut pending) = Self::pending_transfer(env.clone(), id) {
+                pending.amount = scale(pending.amount)?;
+                pending.debit = scale(pending.debit)?;
+                pending.credit = scale(pending.credit)?;
+                env.storage().persistent().set(&DataKeyExt::PendingTransfer(id), &pending);
+            }
+        }
+        
+        // Propuestas de mint del multisig todavía pendientes
+        let next_proposal: u64 = env.storage().instance()
+            .get(&DataKeyExt2::NextProposalId)
+            .unwrap_or(0);
+        for id in 0..next_proposal {
+            if let Some(mut proposal) = Self::proposal(env.clone(), id) {
+                if let AdminAction::Mint(to, amount) = proposal.action {
+                    proposal.action = AdminAction::Mint(to, scale(amount)?);
+                    env.storage().persistent().set(&DataKeyExt2::Proposal(id), &proposal);
+                }
+            }
+        }
+        
+        env.storage().instance().set(&DataKey::Decimals, &new_decimals);
+        env.storage().instance().set(&DataKey::DecimalsMigration, &MigrationStatus::Done);
+        
+        Self::publish(
+            &env,
+            (event_topic::MIGRATE_DECIMALS,),
+            (old_decimals, new_decimals, new_total)
+        );
+        
+        Ok(())
+    }
+    
+    fn set_near_expiry_warnings(env: Env, enabled: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKeyExt::NearExpiryWarnings, &enabled);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "near_expiry_warnings")),
+            enabled
+        );
+        
+        Ok(())
+    }
+    
+    fn near_expiry_warnings(env: Env) -> bool {
+        env.storage().instance()
+            .get(&DataKeyExt::NearExpiryWarnings)
+            .unwrap_or(false)
+    }
+    
+    fn lock(env: Env, account: Address, amount: i128) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let new_locked = Self::locked_balance(env.clone(), account.clone())
+            .checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        if new_locked > Self::balance(env.clone(), account.clone()) {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        Self::write_locked(&env, &account, new_locked);
+        
+        Self::publish(&env, (event_topic::LOCK, account), (amount, new_locked));
+        
+        Ok(())
+    }
+    
+    fn unlock(env: Env, account: Address, amount: i128) -> Result<(), TokenError> {
+        Self::require_admin(&env)?;
+        
+        let locked = Self::locked_balance(env.clone(), account.clone());
+        if amount <= 0 || amount > locked {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let new_locked = locked - amount;
+        Self::write_locked(&env, &account, new_locked);
+        
+        Self::publish(&env, (event_topic::UNLOCK, account), (amount, new_locked));
+        
+        Ok(())
+    }
+    
+    fn locked_balance(env: Env, account: Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKey::Locked(account))
+            .unwrap_or(0)
+    }
+    
+    fn spendable_balance(env: Env, account: Address) -> i128 {
+        Self::balance(env.clone(), account.clone()) - Self::locked_balance(env, account)
+    }
+    
+    fn available_balance(env: Env, account: Address) -> i128 {
+        if Self::is_frozen(env.clone(), account.clone())
+            || Self::require_not_paused(&env, PAUSE_TRANSFER).is_err()
+        {
+            return 0;
+        }
+        
+        Self::spendable_balance(env, account)
+    }
+    
+    fn mint_vested(env: Env, to: Address, tranches: Vec<VestTranche>) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_MINT)?;
+        
+        // 2. Misma autorización que mint()
+        let minter = Self::require_minter(&env)?;
+        
+        // 3. Validar el calendario y sumar el total
+        if tranches.is_empty() {
+            return Err(TokenError::InvalidAmount);
+        }
+        Self::validate_batch_len(tranches.len())?;
+        
+        let mut total: i128 = 0;
+        for tranche in tranches.iter() {
+            if tranche.amount <= 0 {
+                return Err(TokenError::InvalidAmount);
+            }
+            total = total.checked_add(tranche.amount)
+                .ok_or(TokenError::OverflowError)?;
+        }
+        
+        // 4. Mintear el total y bloquearlo
+        Self::execute_mint(env.clone(), minter, to.clone(), total)?;
+        let new_locked = Self::locked_balance(env.clone(), to.clone())
+            .checked_add(total)
+            .ok_or(TokenError::OverflowError)?;
+        Self::write_locked(&env, &to, new_locked);
+        
+        // 5. Agregar los tramos al calendario existente
+        let mut schedule = Self::vest_schedule(env.clone(), to.clone());
+        schedule.append(&tranches);
+        Self::write_vest_schedule(&env, &to, &schedule);
+        
+        Self::publish(&env, (event_topic::VEST, to), (total, tranches.len()));
+        
+        Ok(())
+    }
+    
+    fn vest_schedule(env: Env, to: Address) -> Vec<VestTranche> {
+        env.storage().persistent()
+            .get(&DataKeyExt::VestSchedule(to))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    fn claimable_vested(env: Env, to: Address) -> i128 {
+        let ledger = env.ledger().sequence();
+        Self::vest_schedule(env, to)
+            .iter()
+            .filter(|tranche| tranche.unlock_ledger <= ledger)
+            .try_fold(0i128, |total, tranche| total.checked_add(tranche.amount))
+            .unwrap_or(i128::MAX)
+    }
+    
+    fn claim(env: Env, to: Address) -> Result<i128, TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        to.require_auth();
+        
+        // Separar los tramos desbloqueados de los pendientes
+        let ledger = env.ledger().sequence();
+        let mut claimable: i128 = 0;
+        let mut pending = Vec::new(&env);
+        for tranche in Self::vest_schedule(env.clone(), to.clone()).iter() {
+            if tranche.unlock_ledger <= ledger {
+                claimable = claimable.checked_add(tranche.amount)
+                    .ok_or(TokenError::OverflowError)?;
+            } else {
+                pending.push_back(tranche);
+            }
+        }
+        
+        if claimable == 0 {
+            return Ok(0);
+        }
+        
+        // El bloqueo pudo bajar por unlock() o burn_proportional()
+        let locked = Self::locked_balance(env.clone(), to.clone());
+        let released = claimable.min(locked);
+        let new_locked = locked - released;
+        Self::write_locked(&env, &to, new_locked);
+        Self::write_vest_schedule(&env, &to, &pending);
+        
+        Self::publish(&env, (event_topic::CLAIM, to), (released, new_locked));
+        
+        Ok(released)
+    }
+    
+    fn burn_proportional(env: Env, from: Address, amount: i128) -> Result<(), TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_BURN)?;
+        
+        from.require_auth();
+        
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        Self::require_whole_units(&env, amount)?;
+        
+        Self::require_not_frozen(&env, &from)?;
+        Self::require_no_cooldown(&env, &from)?;
+        Self::require_not_protected(&env, &from)?;
+        
+        let balance = Self::balance(env.clone(), from.clone());
+        if balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        // Parte bloqueada redondeada hacia abajo; el resto sale del libre
+        // (nunca supera el libre porque floor deja el libre redondeado hacia arriba)
+        let locked = Self::locked_balance(env.clone(), from.clone());
+        let from_locked = amount.checked_mul(locked)
+            .ok_or(TokenError::OverflowError)?
+            / balance;
+        let from_unlocked = amount.checked_sub(from_locked)
+            .ok_or(TokenError::OverflowError)?;
+        
+        let new_balance = balance.checked_sub(amount)
+            .ok_or(TokenError::OverflowError)?;
+        let new_locked = locked.checked_sub(from_locked)
+            .ok_or(TokenError::OverflowError)?;
+        Self::write_balance(&env, &from, new_balance);
+        Self::write_locked(&env, &from, new_locked);
+        
+        let new_total = Self::reduce_supply(&env, amount)?;
+        
+        Self::publish(
+            &env,
+            (event_topic::BURN_PROPORTIONAL, from),
+            (amount, from_locked, from_unlocked, new_balance, new_total)
+        );
+        
+        Ok(())
+    }
+    
+    fn last_event_seq(env: Env) -> u64 {
+        env.storage().instance()
+            .get(&DataKeyExt::EventSeq)
+            .unwrap_or(0)
+    }
+    
+    fn categorize_error(_env: Env, code: u32) -> u32 {
+        error_category(code)
+    }
+    
+    fn distribute(env: Env, to: Address, amount: i128) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        // Equivale a mintear para el usuario: la misma pausa lo bloquea
+        Self::require_not_paused(&env, PAUSE_MINT)?;
+        
+        // 2. Solo el admin reparte la reserva
+        Self::require_admin(&env)?;
+        
+        // 3. Validaciones
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let reserve = Self::reserve(env.clone()).ok_or(TokenError::InvalidConfig)?;
+        if to == reserve {
+            return Err(TokenError::InvalidRecipient);
+        }
+        
+        if !Self::allow_mint_to_frozen(env.clone()) {
+            Self::require_not_frozen(&env, &to)?;
+        }
+        
+        Self::require_holder_slot(&env, &to)?;
+        
+        if Self::spendable_balance(env.clone(), reserve.clone()) < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        // 4. Mover de la reserva al destinatario (sin fee, supply intacto)
+        let new_reserve_balance = Self::balance(env.clone(), reserve.clone())
+            .checked_sub(amount)
+            .ok_or(TokenError::OverflowError)?;
+        let new_balance = Self::balance(env.clone(), to.clone())
+            .checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        
+        Self::write_balance(&env, &reserve, new_reserve_balance);
+        Self::write_balance(&env, &to, new_balance);
+        Self::record_receipt(&env, &to);
+        
+        // 5. Emitir evento
+        Self::publish(
+            &env,
+            (event_topic::DISTRIBUTE, to),
+            (amount, new_balance, new_reserve_balance)
+        );
+        
+        Ok(())
+    }
+    
+    fn reserve(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKeyExt::Reserve)
+    }
+    
+    fn migrate_from(
+        env: Env,
+        holder: Address,
+        amount: i128,
+        old_token: Address
+    ) -> Result<i128, TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_MINT)?;
+        
+        // 2. Solo se acepta el token viejo configurado
+        if Self::legacy_token(env.clone()).as_ref() != Some(&old_token) {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        holder.require_auth();
+        
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let minted = Self::floor_whole_units(
+            &env,
+            amount.checked_mul(Self::migration_rate(env.clone()) as i128)
+                .ok_or(TokenError::OverflowError)?
+                / BPS_DENOMINATOR as i128
+        );
+        if minted <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        // 3. Quemar en el token viejo (falla si el holder no tiene saldo)
+        match token::Client::new(&env, &old_token).try_burn(&holder, &amount) {
+            Ok(Ok(())) => {}
+            _ => return Err(TokenError::InsufficientBalance),
+        }
+        
+        // 4. Mintear el equivalente (el holder es quien autorizó)
+        Self::execute_mint(env.clone(), holder.clone(), holder.clone(), minted)?;
+        
+        Self::publish(
+            &env,
+            (event_topic::MIGRATE_FROM, holder, old_token),
+            (amount, minted)
+        );
+        
+        Ok(minted)
+    }
+    
+    fn set_legacy_token(env: Env, token: Option<Address>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        match &token {
+            Some(address) => env.storage().instance().set(&DataKeyExt::LegacyToken, address),
+            None => env.storage().instance().remove(&DataKeyExt::LegacyToken),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "legacy_token")),
+            token
+        );
+        
+        Ok(())
+    }
+    
+    fn legacy_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKeyExt::LegacyToken)
+    }
+    
+    fn set_migration_rate(env: Env, bps: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if bps == 0 {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        env.storage().instance().set(&DataKeyExt::MigrationRate, &bps);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "migration_rate")),
+            bps
+        );
+        
+        Ok(())
+    }
+    
+    fn migration_rate(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt::MigrationRate)
+            .unwrap_or(BPS_DENOMINATOR)
+    }
+    
+    fn set_max_supply(env: Env, max_supply: Option<i128>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        match max_supply {
+            Some(cap) => {
+                if cap < 0
+                    || cap < Self::total_supply(env.clone())
+                    || cap > Self::max_representable_supply(env.clone())
+                {
+                    return Err(TokenError::InvalidConfig);
+                }
+                env.storage().instance().set(&DataKeyExt::MaxSupply, &cap);
+            }
+            None => env.storage().instance().remove(&DataKeyExt::MaxSupply),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "max_supply")),
+            max_supply
+        );
+        
+        Ok(())
+    }
+    
+    fn max_supply(env: Env) -> Option<i128> {
+        env.storage().instance().get(&DataKeyExt::MaxSupply)
+    }
+    
+    fn max_representable_supply(env: Env) -> i128 {
+        // decimals <= MAX_DECIMALS, así que el exponente nunca es negativo
+        let migration_headroom = 10i128.pow(MAX_DECIMALS - Self::decimals(env));
+        i128::MAX / BPS_DENOMINATOR as i128 / migration_headroom
+    }
+    
+    fn mintable_headroom(env: Env) -> i128 {
+        match Self::max_supply(env.clone()) {
+            Some(cap) => (cap - Self::total_supply(env)).max(0),
+            None => i128::MAX,
+        }
+    }
+    
+    fn can_mint(env: Env, amount: i128) -> bool {
+        amount > 0 && amount <= Self::mintable_headroom(env)
+    }
+    
+    fn set_inflation(
+        env: Env,
+        rate_bps_per_window: u32,
+        window_ledgers: u32,
+        recipient: Address
+    ) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if rate_bps_per_window == 0 {
+            env.storage().instance().remove(&DataKeyExt::Inflation);
+            env.storage().instance().remove(&DataKeyExt::LastInflationLedger);
+        } else {
+            if rate_bps_per_window > BPS_DENOMINATOR
+                || window_ledgers == 0
+                || recipient == env.current_contract_address()
+            {
+                return Err(TokenError::InvalidConfig);
+            }
+            
+            let config = InflationConfig {
+                rate_bps: rate_bps_per_window,
+                window_ledgers,
+                recipient: recipient.clone(),
+            };
+            env.storage().instance().set(&DataKeyExt::Inflation, &config);
+            env.storage().instance().set(
+                &DataKeyExt::LastInflationLedger,
+                &env.ledger().sequence()
+            );
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "inflation")),
+            (rate_bps_per_window, window_ledgers, recipient)
+        );
+        
+        Ok(())
+    }
+    
+    fn inflation(env: Env) -> Option<InflationConfig> {
+        env.storage().instance().get(&DataKeyExt::Inflation)
+    }
+    
+    fn last_inflation_ledger(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt::LastInflationLedger)
+            .unwrap_or(0)
+    }
+    
+    fn mint_inflation(env: Env) -> Result<i128, TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_MINT)?;
+        
+        let config = Self::inflation(env.clone()).ok_or(TokenError::InvalidConfig)?;
+        
+        // 1. Una ventana completa por llamada
+        let window_start = Self::last_inflation_ledger(env.clone());
+        let window_end = window_start.checked_add(config.window_ledgers)
+            .ok_or(TokenError::OverflowError)?;
+        if env.ledger().sequence() < window_end {
+            return Err(TokenError::InflationNotDue);
+        }
+        env.storage().instance().set(&DataKeyExt::LastInflationLedger, &window_end);
+        
+        // 2. Mintear sobre el supply actual (0 si no hay supply)
+        let amount = Self::floor_whole_units(
+            &env,
+            Self::total_supply(env.clone())
+                .checked_mul(config.rate_bps as i128)
+                .ok_or(TokenError::OverflowError)?
+                / BPS_DENOMINATOR as i128
+        );
+        if amount > 0 {
+            let this = env.current_contract_address();
+            Self::execute_mint(env.clone(), this, config.recipient.clone(), amount)?;
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::INFLATION, config.recipient),
+            (amount, window_end)
+        );
+        
+        Ok(amount)
+    }
+    
+    fn buyback_burn(env: Env, amount: i128) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_BURN)?;
+        
+        // 2. Solo el executor designado
+        let executor = Self::buyback_executor(env.clone()).ok_or(TokenError::Unauthorized)?;
+        executor.require_auth();
+        
+        // 3. Validaciones
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        Self::require_whole_units(&env, amount)?;
+        
+        let treasury = Self::treasury(env.clone()).ok_or(TokenError::InvalidConfig)?;
+        Self::require_not_frozen(&env, &treasury)?;
+        
+        let balance = Self::balance(env.clone(), treasury.clone());
+        if Self::spendable_balance(env.clone(), treasury.clone()) < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        // 4. Quemar del treasury y acumular
+        let new_balance = balance.checked_sub(amount)
+            .ok_or(TokenError::OverflowError)?;
+        Self::write_balance(&env, &treasury, new_balance);
+        let new_total = Self::reduce_supply(&env, amount)?;
+        
+        let total_buyback = Self::total_buyback_burned(env.clone())
+            .checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        env.storage().instance().set(&DataKeyExt::TotalBuybackBurned, &total_buyback);
+        
+        Self::publish(
+            &env,
+            (event_topic::BUYBACK, treasury, executor),
+            (amount, new_balance, new_total, total_buyback)
+        );
+        
+        Ok(())
+    }
+    
+    fn set_buyback_executor(env: Env, executor: Option<Address>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        match &executor {
+            Some(address) => env.storage().instance().set(&DataKeyExt::BuybackExecutor, address),
+            None => env.storage().instance().remove(&DataKeyExt::BuybackExecutor),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "buyback_executor")),
+            executor
+        );
+        
+        Ok(())
+    }
+    
+    fn buyback_executor(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKeyExt::BuybackExecutor)
+    }
+    
+    fn total_buyback_burned(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKeyExt::TotalBuybackBurned)
+            .unwrap_or(0)
+    }
+    
+    fn set_notification_relay(env: Env, relay: Address) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKeyExt::NotificationRelay, &relay);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "notification_relay")),
+            Some(relay)
+        );
+        
+        Ok(())
+    }
+    
+    fn clear_notification_relay(env: Env) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        env.storage().instance().remove(&DataKeyExt::NotificationRelay);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "notification_relay")),
+            None::<Address>
+        );
+        
+        Ok(())
+    }
+    
+    fn notification_relay(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKeyExt::NotificationRelay)
+    }
+    
+    fn set_volume_limit(env: Env, account: Address, limit: Option<i128>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        let key = DataKeyExt::VolumeLimit(account.clone());
+        match limit {
+            Some(value) => {
+                if value < 0 {
+                    return Err(TokenError::InvalidConfig);
+                }
+                env.storage().persistent().set(&key, &(value, Self::decimals(env.clone())));
+                env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        
+        Self::publish(&env, (event_topic::VOLUME_LIMIT, account), limit);
+        
+        Ok(())
+    }
+    
+    fn set_default_volume_limit(env: Env, limit: Option<i128>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        match limit {
+            Some(value) => {
+                if value < 0 {
+                    return Err(TokenError::InvalidConfig);
+                }
+                env.storage().instance().set(&DataKeyExt::DefaultVolumeLimit, &value);
+            }
+            None => env.storage().instance().remove(&DataKeyExt::DefaultVolumeLimit),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "default_volume_limit")),
+            limit
+        );
+        
+        Ok(())
+    }
+    
+    fn set_volume_window(env: Env, window_ledgers: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if window_ledgers == 0 {
+            return Err(TokenError::InvalidConfig);
+        }
+        env.storage().instance().set(&DataKeyExt::VolumeWindow, &window_ledgers);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "volume_window")),
+            window_ledgers
+        );
+        
+        Ok(())
+    }
+    
+    fn volume_limit(env: Env, account: Address) -> Option<i128> {
+        env.storage().persistent()
+            .get::<DataKeyExt, (i128, u32)>(&DataKeyExt::VolumeLimit(account))
+            .map(|(limit, decimals)| Self::rescale(&env, limit, decimals))
+            .or_else(|| Self::default_volume_limit(env.clone()))
+    }
+    
+    fn default_volume_limit(env: Env) -> Option<i128> {
+        env.storage().instance().get(&DataKeyExt::DefaultVolumeLimit)
+    }
+    
+    fn volume_window(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt::VolumeWindow)
+            .unwrap_or(VOLUME_WINDOW_LEDGERS)
+    }
+    
+    fn volume_used(env: Env, account: Address) -> i128 {
+        let window = Self::current_volume_window(&env);
+        env.storage().temporary()
+            .get(&DataKeyExt::VolumeUsed(account, window))
+            .unwrap_or(0)
+    }
+    
+    fn volume_remaining(env: Env, account: Address) -> Option<i128> {
+        Self::volume_limit(env.clone(), account.clone())
+            .map(|limit| (limit - Self::volume_used(env, account)).max(0))
+    }
+    
+    fn drain_reserve(env: Env, to: Address) -> Result<i128, TokenError> {
+        let admin = Self::require_admin(&env)?;
+        
+        if !Self::drain_enabled(env.clone()) || !Self::is_paused(env.clone()) {
+            return Err(TokenError::DrainUnavailable);
+        }
+        
+        let contract = env.current_contract_address();
+        if to == contract {
+            return Err(TokenError::InvalidRecipient);
+        }
+        
+        // La parte bloqueada queda en el contrato junto con su Locked
+        let amount = Self::spendable_balance(env.clone(), contract.clone());
+        if amount <= 0 {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        Self::require_holder_slot(&env, &to)?;
+        
+        let new_self_balance = Self::self_balance(env.clone())
+            .checked_sub(amount)
+            .ok_or(TokenError::OverflowError)?;
+        let new_to_balance = Self::balance(env.clone(), to.clone())
+            .checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        Self::write_balance(&env, &contract, new_self_balance);
+        Self::write_balance(&env, &to, new_to_balance);
+        
+        Self::publish(&env, (event_topic::DRAIN, to, admin), (amount, new_to_balance));
+        
+        Ok(amount)
+    }
+    
+    fn drain_enabled(env: Env) -> bool {
+        env.storage().instance().has(&DataKeyExt::DrainEnabled)
+    }
+    
+    fn set_require_non_empty(env: Env, enabled: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if enabled {
+            env.storage().instance().set(&DataKeyExt::RequireNonEmpty, &true);
+        } else {
+            env.storage().instance().remove(&DataKeyExt::RequireNonEmpty);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "require_non_empty")),
+            enabled
+        );
+        
+        Ok(())
+    }
+    
+    fn require_non_empty(env: Env) -> bool {
+        env.storage().instance().has(&DataKeyExt::RequireNonEmpty)
+    }
+    
+    fn set_non_transferable_allowance(env: Env, enabled: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if enabled {
+            env.storage().instance().set(&DataKeyExt::NonTransferableAllowance, &true);
+        } else {
+            env.storage().instance().remove(&DataKeyExt::NonTransferableAllowance);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "non_transferable_allowance")),
+            enabled
+        );
+        
+        Ok(())
+    }
+    
+    fn non_transferable_allowance(env: Env) -> bool {
+        env.storage().instance().has(&DataKeyExt::NonTransferableAllowance)
+    }
+    
+    fn lock_config(env: Env) -> Result<(), TokenError> {
+        let admin = Self::require_config_admin(&env)?;
+        
+        env.storage().instance().set(&DataKeyExt::ConfigLocked, &true);
+        
+        Self::publish(&env, (event_topic::LOCK_CONFIG, admin), ());
+        
+        Ok(())
+    }
+    
+    fn config_locked(env: Env) -> bool {
+        env.storage().instance().has(&DataKeyExt::ConfigLocked)
+    }
+    
+    fn set_transfer_delay(env: Env, threshold: i128, delay_ledgers: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if threshold < 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        if threshold == 0 {
+            env.storage().instance().remove(&DataKeyExt::DelayThreshold);
+            env.storage().instance().remove(&DataKeyExt::SettlementDelay);
+        } else {
+            if delay_ledgers == 0 {
+                return Err(TokenError::InvalidConfig);
+            }
+            env.storage().instance().set(&DataKeyExt::DelayThreshold, &threshold);
+            env.storage().instance().set(&DataKeyExt::SettlementDelay, &delay_ledgers);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "transfer_delay")),
+            (threshold, delay_ledgers)
+        );
+        
+        Ok(())
+    }
+    
+    fn delay_threshold(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKeyExt::DelayThreshold)
+            .unwrap_or(0)
+    }
+    
+    fn settlement_delay(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt::SettlementDelay)
+            .unwrap_or(0)
+    }
+    
+    fn pending_transfer(env: Env, id: u64) -> Option<PendingTransfer> {
+        env.storage().persistent().get(&DataKeyExt::PendingTransfer(id))
+    }
+    
+    fn settle_transfer(env: Env, id: u64) -> Result<(), TokenError> {
+        Self::require_not_paused(&env, PAUSE_TRANSFER)?;
+        
+        let pending = Self::pending_transfer(env.clone(), id)
+            .ok_or(TokenError::UnknownPendingTransfer)?;
+        if env.ledger().sequence() < pending.settle_ledger {
+            return Err(TokenError::OutsideSettlementWindow);
+        }
+        
+        Self::require_holder_slot(&env, &pending.to)?;
+        let new_to_balance = Self::balance(env.clone(), pending.to.clone())
+            .checked_add(pending.credit)
+            .ok_or(TokenError::OverflowError)?;
+        
+        env.storage().persistent().remove(&DataKeyExt::PendingTransfer(id));
+        Self::write_balance(&env, &pending.to, new_to_balance);
+        Self::record_receipt(&env, &pending.to);
+        Self::record_counterparties(&env, &pending.from, &pending.to);
+        let fee = pending.debit
+            .checked_sub(pending.credit)
+            .ok_or(TokenError::OverflowError)?;
+        Self::distribute_fee(&env, &pending.from, fee)?;
+        
+        Self::publish(
+            &env,
+            (event_topic::SETTLE, pending.from, pending.to),
+            (id, pending.amount, new_to_balance)
+        );
+        
+        Ok(())
+    }
+    
+    fn cancel_transfer(env: Env, id: u64) -> Result<(), TokenError> {
+        let pending = Self::pending_transfer(env.clone(), id)
+            .ok_or(TokenError::UnknownPendingTransfer)?;
+        pending.from.require_auth();
+        
+        if env.ledger().sequence() >= pending.settle_ledger {
+            return Err(TokenError::OutsideSettlementWindow);
+        }
+        
+        let new_from_balance = Self::balance(env.clone(), pending.from.clone())
+            .checked_add(pending.debit)
+            .ok_or(TokenError::OverflowError)?;
+        
+        env.storage().persistent().remove(&DataKeyExt::PendingTransfer(id));
+        Self::write_balance(&env, &pending.from, new_from_balance);
+        
+        Self::publish(
+            &env,
+            (event_topic::CANCEL, pending.from, pending.to),
+            (id, pending.amount, new_from_balance)
+        );
+        
+        Ok(())
+    }
+    
+    fn burn_redeem(env: Env, from: Address, amount: i128) -> Result<i128, TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_BURN)?;
+        
+        let asset = Self::redemption_asset(env.clone()).ok_or(TokenError::InvalidConfig)?;
+        
+        // 2. Requiere autorización del dueño de los tokens
+        from.require_auth();
+        
+        // 3. Calcular el subyacente y verificar que la reserva alcance
+        let released = amount.checked_mul(Self::redemption_rate(env.clone()) as i128)
+            .ok_or(TokenError::OverflowError)?
+            / BPS_DENOMINATOR as i128;
+        if released <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if Self::foreign_balance(env.clone(), asset.clone()) < released {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        // 4. Quemar y acumular lo redimido
+        let (new_balance, new_total) = Self::burn_balance(&env, &from, amount)?;
+        let total_redeemed = Self::total_redeemed(env.clone())
+            .checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        env.storage().instance().set(&DataKeyExt::TotalRedeemed, &total_redeemed);
+        
+        // 5. Liberar la reserva
+        token::Client::new(&env, &asset).transfer(
+            &env.current_contract_address(),
+            &from,
+            &released
+        );
+        
+        Self::publish(
+            &env,
+            (event_topic::REDEEM, from, asset),
+            (amount, released, new_balance, new_total)
+        );
+        
+        Ok(released)
+    }
+    
+    fn set_redemption_asset(env: Env, asset: Option<Address>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        // El propio token no puede respaldarse a sí mismo
+        if asset.as_ref() == Some(&env.current_contract_address()) {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        match &asset {
+            Some(address) => env.storage().instance().set(&DataKeyExt::RedemptionAsset, address),
+            None => env.storage().instance().remove(&DataKeyExt::RedemptionAsset),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "redemption_asset")),
+            asset
+        );
+        
+        Ok(())
+    }
+    
+    fn redemption_asset(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKeyExt::RedemptionAsset)
+    }
+    
+    fn set_redemption_rate(env: Env, bps: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if bps == 0 {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        env.storage().instance().set(&DataKeyExt::RedemptionRate, &bps);
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "redemption_rate")),
+            bps
+        );
+        
+        Ok(())
+    }
+    
+    fn redemption_rate(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt::RedemptionRate)
+            .unwrap_or(BPS_DENOMINATOR)
+    }
+    
+    fn total_redeemed(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKeyExt::TotalRedeemed)
+            .unwrap_or(0)
+    }
+    
+    fn set_block_self_approve(env: Env, enabled: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if enabled {
+            env.storage().instance().set(&DataKeyExt::BlockSelfApprove, &true);
+        } else {
+            env.storage().instance().remove(&DataKeyExt::BlockSelfApprove);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "block_self_approve")),
+            enabled
+        );
+        
+        Ok(())
+    }
+    
+    fn block_self_approve(env: Env) -> bool {
+        env.storage().instance().has(&DataKeyExt::BlockSelfApprove)
+    }
+    
+    fn propose(env: Env, proposer: Address, action: AdminAction) -> Result<u64, TokenError> {
+        let multisig = Self::require_signer(&env, &proposer)?;
+        
+        let id: u64 = env.storage().instance()
+            .get(&DataKeyExt2::NextProposalId)
+            .unwrap_or(0);
+        env.storage().instance().set(&DataKeyExt2::NextProposalId, &(id + 1));
+        
+        Self::publish(&env, (event_topic::PROPOSE, id, proposer.clone()), action.clone());
+        
+        let proposal = Proposal {
+            action,
+            approvals: Vec::from_array(&env, [proposer]),
+        };
+        Self::advance_proposal(&env, id, proposal, multisig.threshold)?;
+        
+        Ok(id)
+    }
+    
+    fn approve_proposal(env: Env, signer: Address, id: u64) -> Result<bool, TokenError> {
+        let multisig = Self::require_signer(&env, &signer)?;
+        
+        let mut proposal = Self::proposal(env.clone(), id).ok_or(TokenError::UnknownProposal)?;
+        if proposal.approvals.contains(&signer) {
+            return Err(TokenError::AlreadyApproved);
+        }
+        proposal.approvals.push_back(signer.clone());
+        
+        Self::publish(
+            &env,
+            (event_topic::APPROVE_PROPOSAL, id, signer),
+            proposal.approvals.len()
+        );
+        
+        Self::advance_proposal(&env, id, proposal, multisig.threshold)
+    }
+    
+    fn proposal(env: Env, id: u64) -> Option<Proposal> {
+        env.storage().persistent().get(&DataKeyExt2::Proposal(id))
+    }
+    
+    fn multisig(env: Env) -> Option<Multisig> {
+        let signers = env.storage().instance().get(&DataKeyExt2::Signers)?;
+        let threshold = env.storage().instance().get(&DataKeyExt2::Threshold)?;
+        Some(Multisig { signers, threshold })
+    }
+    
+    fn transfer_with_receipt(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<TransferReceipt, TokenError> {
+        let result = Self::execute_transfer(env.clone(), from.clone(), to, amount);
+        Self::report_failure(&env, event_topic::TRANSFER, from, result)
+    }
+    
+    fn set_ttl_tiers(env: Env, tiers: Option<TtlTiers>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        match &tiers {
+            Some(value) => {
+                if value.active_window == 0
+                    || value.baseline_extend_to == 0
+                    || value.active_extend_to < value.baseline_extend_to
+                    || value.active_extend_to > env.storage().max_ttl()
+                {
+                    return Err(TokenError::InvalidConfig);
+                }
+                env.storage().instance().set(&DataKeyExt2::TtlTiers, value);
+            }
+            None => env.storage().instance().remove(&DataKeyExt2::TtlTiers),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "ttl_tiers")),
+            tiers
+        );
+        
+        Ok(())
+    }
+    
+    fn ttl_tiers(env: Env) -> Option<TtlTiers> {
+        env.storage().instance().get(&DataKeyExt2::TtlTiers)
+    }
+    
+    fn balance_proof(env: Env, account: Address) -> BalanceProof {
+        BalanceProof {
+            balance: Self::balance(env.clone(), account.clone()),
+            account,
+            ledger: env.ledger().sequence(),
+            supply: Self::total_supply(env),
+        }
+    }
+    
+    fn set_config_change_cooldown(env: Env, ledgers: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if ledgers == 0 {
+            env.storage().instance().remove(&DataKeyExt2::ConfigChangeCooldown);
+        } else {
+            env.storage().instance().set(&DataKeyExt2::ConfigChangeCooldown, &ledgers);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "config_change_cooldown")),
+            ledgers
+        );
+        
+        Ok(())
+    }
+    
+    fn config_change_cooldown(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt2::ConfigChangeCooldown)
+            .unwrap_or(0)
+    }
+    
+    fn last_config_change(env: Env) -> Option<u32> {
+        env.storage().instance().get(&DataKeyExt2::LastConfigChange)
+    }
+    
+    fn set_price_condition(env: Env, condition: Option<PriceCondition>) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        match &condition {
+            Some(value) => {
+                if value.oracle == env.current_contract_address() {
+                    return Err(TokenError::InvalidConfig);
+                }
+                env.storage().instance().set(&DataKeyExt2::PriceOracle, value);
+            }
+            None => env.storage().instance().remove(&DataKeyExt2::PriceOracle),
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "price_condition")),
+            condition
+        );
+        
+        Ok(())
+    }
+    
+    fn price_condition(env: Env) -> Option<PriceCondition> {
+        env.storage().instance().get(&DataKeyExt2::PriceOracle)
+    }
+    
+    fn set_reward_rate(env: Env, rate: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        // Cerrar el índice con el rate anterior
+        let index = Self::reward_index(&env);
+        env.storage().instance().set(&DataKeyExt2::RewardIndex, &index);
+        env.storage().instance().set(&DataKeyExt2::RewardIndexLedger, &env.ledger().sequence());
+        
+        if rate == 0 {
+            env.storage().instance().remove(&DataKeyExt2::RewardRate);
+        } else {
+            env.storage().instance().set(&DataKeyExt2::RewardRate, &rate);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "reward_rate")),
+            rate
+        );
+        
+        Ok(())
+    }
+    
+    fn reward_rate(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt2::RewardRate)
+            .unwrap_or(0)
+    }
+    
+    fn pending_rewards(env: Env, account: Address) -> i128 {
+        let accrued: i128 = env.storage().persistent()
+            .get(&DataKeyExt2::AccruedRewards(account.clone()))
+            .unwrap_or(0);
+        accrued.saturating_add(Self::unsettled_rewards(&env, &account))
+    }
+    
+    fn claim_rewards(env: Env, account: Address) -> Result<i128, TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_MINT)?;
+        account.require_auth();
+        
+        // 1. Liquidar hasta este ledger y vaciar lo acumulado (con
+        // WholeUnitsOnly la fracción queda acumulada para el próximo claim)
+        Self::accrue_rewards(&env, &account);
+        let key = DataKeyExt2::AccruedRewards(account.clone());
+        let accrued: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        let amount = Self::floor_whole_units(&env, accrued);
+        if amount == 0 {
+            return Ok(0);
+        }
+        if amount == accrued {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &(accrued - amount));
+        }
+        
+        // 2. Mintear (valida max supply, congelamiento y holder cap)
+        let this = env.current_contract_address();
+        Self::execute_mint(env.clone(), this, account.clone(), amount)?;
+        
+        Self::publish(&env, (event_topic::REWARD, account), amount);
+        
+        Ok(amount)
+    }
+    
+    fn clawback_batch(
+        env: Env,
+        froms: Vec<Address>,
+        amounts: Vec<i128>
+    ) -> Result<i128, TokenError> {
+        Self::require_admin(&env)?;
+        
+        Self::validate_batch_len(froms.len())?;
+        if froms.len() != amounts.len() {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        // 1. Debitar cada cuenta (una cuenta repetida se debita dos veces)
+        let mut total: i128 = 0;
+        for (from, amount) in froms.iter().zip(amounts.iter()) {
+            if amount <= 0 {
+                return Err(TokenError::InvalidAmount);
+            }
+            Self::require_whole_units(&env, amount)?;
+            Self::require_not_protected(&env, &from)?;
+            
+            // Solo el balance libre: Locked queda intacto (ver el doc)
+            let balance = Self::balance(env.clone(), from.clone());
+            if Self::spendable_balance(env.clone(), from.clone()) < amount {
+                return Err(TokenError::InsufficientBalance);
+            }
+            let new_balance = balance.checked_sub(amount)
+                .ok_or(TokenError::OverflowError)?;
+            Self::write_balance(&env, &from, new_balance);
+            total = total.checked_add(amount)
+                .ok_or(TokenError::OverflowError)?;
+            
+            Self::publish(&env, (event_topic::CLAWBACK, from), (amount, new_balance));
+        }
+        
+        // 2. Una sola actualización del supply
+        if total > 0 {
+            Self::reduce_supply(&env, total)?;
+        }
+        
+        Ok(total)
+    }
+    
+    fn deploy_ledger(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt2::DeployLedger)
+            .unwrap_or(0)
+    }
+    
+    fn age_ledgers(env: Env) -> u32 {
+        match env.storage().instance().get::<DataKeyExt2, u32>(&DataKeyExt2::DeployLedger) {
+            Some(deployed) => env.ledger().sequence().saturating_sub(deployed),
+            None => 0,
+        }
+    }
+    
+    fn set_whole_units_only(env: Env, enabled: bool) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if enabled {
+            env.storage().instance().set(&DataKeyExt2::WholeUnitsOnly, &true);
+        } else {
+            env.storage().instance().remove(&DataKeyExt2::WholeUnitsOnly);
+        }
+        // Un fee fijo fraccionario dejaría netos fraccionarios
+        Self::require_whole_units(&env, Self::flat_fee(env.clone()))?;
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "whole_units_only")),
+            enabled
+        );
+        
+        Ok(())
+    }
+    
+    fn whole_units_only(env: Env) -> bool {
+        env.storage().instance().has(&DataKeyExt2::WholeUnitsOnly)
+    }
+    
+    fn set_early_unlock_penalty(env: Env, bps: u32) -> Result<(), TokenError> {
+        Self::require_config_admin(&env)?;
+        
+        if bps > BPS_DENOMINATOR {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        if bps == 0 {
+            env.storage().instance().remove(&DataKeyExt2::EarlyUnlockPenalty);
+        } else {
+            env.storage().instance().set(&DataKeyExt2::EarlyUnlockPenalty, &bps);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::CONFIG, Symbol::new(&env, "early_unlock_penalty")),
+            bps
+        );
+        
+        Ok(())
+    }
+    
+    fn early_unlock_penalty(env: Env) -> u32 {
+        env.storage().instance()
+            .get(&DataKeyExt2::EarlyUnlockPenalty)
+            .unwrap_or(0)
+    }
+    
+    fn total_penalty_burned(env: Env) -> i128 {
+        env.storage().instance()
+            .get(&DataKeyExt2::TotalPenaltyBurned)
+            .unwrap_or(0)
+    }
+    
+    fn emergency_unlock(env: Env, account: Address) -> Result<i128, TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_BURN)?;
+        
+        // Sin penalidad sería un claim() anticipado gratis
+        let penalty_bps = Self::early_unlock_penalty(env.clone());
+        if penalty_bps == 0 {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        // 2. Solo la propia cuenta puede salir anticipadamente
+        account.require_auth();
+        Self::require_not_frozen(&env, &account)?;
+        
+        // 3. Separar los tramos pendientes de los ya desbloqueados
+        let ledger = env.ledger().sequence();
+        let mut vesting: i128 = 0;
+        let mut unlocked = Vec::new(&env);
+        for tranche in Self::vest_schedule(env.clone(), account.clone()).iter() {
+            if tranche.unlock_ledger > ledger {
+                vesting = vesting.checked_add(tranche.amount)
+                    .ok_or(TokenError::OverflowError)?;
+            } else {
+                unlocked.push_back(tranche);
+            }
+        }
+        
+        // El bloqueo pudo bajar por unlock() o burn_proportional()
+        let locked = Self::locked_balance(env.clone(), account.clone());
+        let vesting = vesting.min(locked);
+        if vesting == 0 {
+            return Ok(0);
+        }
+        
+        // 4. Penalidad redondeada hacia abajo
+        let penalty = Self::floor_whole_units(
+            &env,
+            vesting.checked_mul(penalty_bps as i128)
+                .ok_or(TokenError::OverflowError)?
+                / BPS_DENOMINATOR as i128
+        );
+        let released = vesting.checked_sub(penalty)
+            .ok_or(TokenError::OverflowError)?;
+        
+        // 5. Liberar los tramos pendientes (lo bloqueado con lock() queda)
+        let new_locked = locked.checked_sub(vesting)
+            .ok_or(TokenError::OverflowError)?;
+        Self::write_locked(&env, &account, new_locked);
+        Self::write_vest_schedule(&env, &account, &unlocked);
+        
+        // 6. Quemar la penalidad
+        let new_balance = Self::balance(env.clone(), account.clone())
+            .checked_sub(penalty)
+            .ok_or(TokenError::OverflowError)?;
+        let mut new_total = Self::total_supply(env.clone());
+        if penalty > 0 {
+            Self::write_balance(&env, &account, new_balance);
+            new_total = Self::reduce_supply(&env, penalty)?;
+            
+            let total_penalty = Self::total_penalty_burned(env.clone())
+                .checked_add(penalty)
+                .ok_or(TokenError::OverflowError)?;
+            env.storage().instance().set(&DataKeyExt2::TotalPenaltyBurned, &total_penalty);
+        }
+        
+        Self::publish(
+            &env,
+            (event_topic::EARLY_UNLOCK, account),
+            (released, penalty, new_balance, new_total)
+        );
+        
+        Ok(released)
+    }
+    
+    fn balance_and_extend(env: Env, account: Address) -> i128 {
+        let key = DataKey::Balance(account.clone());
+        let balance = match env.storage().persistent().get::<DataKey, i128>(&key) {
+            Some(balance) => balance,
+            None => return 0,
+        };
+        
+        // Mismo TTL que write_balance()
+        let extend_to = Self::balance_extend_to(&env, &account);
+        if Self::near_expiry_warnings(env.clone()) {
+            Self::track_balance_ttl(&env, &account, extend_to);
+        }
+        env.storage().persistent().extend_ttl(&key, extend_to / 2, extend_to);
+        
+        balance
+    }
+}
+
+
+/// Configuración resultante que se valida antes de guardar cambios
+/// 
+/// Los setters parten de la configuración actual, aplican su cambio
+/// y la pasan por validate_config()
+struct ConfigCheck {
+    admin: Address,
+    fee_bps: u32,
+    flat_fee: i128,
+    treasury: Option<Address>,
+    xlm_fee: i128,
+    xlm_fee_collector: Option<Address>,
+}
+
+impl ConfigCheck {
+    /// Configuración por defecto (sin fees) para un admin dado
+    fn new(admin: Address) -> Self {
+        ConfigCheck {
+            admin,
+            fee_bps: 0,
+            flat_fee: 0,
+            treasury: None,
+            xlm_fee: 0,
+            xlm_fee_collector: None,
+        }
+    }
+}
+
+/// Resultado calculado de una transferencia, antes de escribir estado
+struct TransferPlan {
+    /// Total debitado a `from` (amount, o amount + fee con SenderPaysExtra)
+    debit: i128,
+    new_from_balance: i128,
+    new_to_balance: i128,
+    fee: i128,
+    /// (receptor, parte del fee, balance final) por cada receptor del fee
+    fee_credits: Vec<(Address, i128, i128)>,
+}
+
+/// Funciones internas del contrato (no se exportan como métodos)
+impl TokenBDB {
+    /// Lógica de transfer() y transfer_with_receipt() (ver report_failure)
+    fn execute_transfer(
+        env: Env, 
+        from: Address, 
+        to: Address, 
+        amount: i128
+    ) -> Result<TransferReceipt, TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_TRANSFER)?;
+        
+        // 2. Verificar autorización del sender
+        from.require_auth();
+        
+        // 3. Validaciones (amount > 0, sin auto-transferencia)
+        Self::validate_transfer(&env, &from, &to, amount)?;
+        
+        // 4. Calcular balances (verifica saldo y aplica el fee)
+        let plan = Self::plan_transfer(&env, &from, &to, amount)?;
+        
+        // 5. Cobrar el fee en XLM (si está configurado) antes de mover tokens
+        Self::charge_xlm_fee(&env, &from)?;
+        
+        // Por encima del umbral los tokens quedan retenidos hasta settle_transfer
+        if Self::exceeds_delay_threshold(&env, amount) {
+            let (id, new_from_balance) = Self::hold_transfer(&env, &from, &to, amount, &plan)?;
+            return Ok(TransferReceipt {
+                amount,
+                fee: plan.fee,
+                new_from_balance,
+                new_to_balance: Self::balance(env.clone(), to),
+                ledger: env.ledger().sequence(),
+                pending_id: Some(id),
+            });
+        }
+        
+        // 6. Actualizar balances con TTL
+        Self::apply_transfer(&env, &from, &to, &plan);
+        Self::record_outgoing_transfer(&env, &from, amount)?;
+        
+        // 7. Emitir evento con balances post-transferencia
+        // (y la secuencia del sender si está activa)
+        let topics = (event_topic::TRANSFER, from.clone(), to.clone());
+        match Self::next_sender_seq(&env, &from)? {
+            Some(seq) => Self::publish(
+                &env,
+                topics,
+                (amount, plan.new_from_balance, plan.new_to_balance, seq)
+            ),
+            None => Self::publish(
+                &env,
+                topics,
+                (amount, plan.new_from_balance, plan.new_to_balance)
+            ),
+        }
+        
+        // 8. Avisar al relay (si existe); su error se ignora a propósito
+        if let Some(relay) = Self::notification_relay(env.clone()) {
+            let _ = env.try_invoke_contract::<(), soroban_sdk::Error>(
+                &relay,
+                &Symbol::new(&env, "notify"),
+                (from, to, amount).into_val(&env)
+            );
+        }
+        
+        Ok(TransferReceipt {
+            amount,
+            fee: plan.fee,
+            new_from_balance: plan.new_from_balance,
+            new_to_balance: plan.new_to_balance,
+            ledger: env.ledger().sequence(),
+            pending_id: None,
+        })
+    }
+    
+    /// Lógica de approve() y approve_no_expiry() (ver report_failure)
+    fn execute_approve(
+        env: Env, 
+        from: Address, 
+        spender: Address, 
+        amount: i128,
+        expiration_ledger: u32
+    ) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_APPROVE)?;
+        
+        // 2. Verificar autorización del owner
+        from.require_auth();
+        
+        // 3. Validación: amount debe ser >= 0 (permitir 0 para revocar)
+        if amount < 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        // Una expiración pasada solo tiene sentido para revocar
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            return Err(TokenError::InvalidExpiration);
+        }
+        
+        Self::require_not_self_spender(&env, &spender, amount)?;
+        Self::check_allowance_cap(&env, &from, &spender, amount)?;
+        
+        // 4. Obtener allowance anterior para el evento
+        let current = Self::read_allowance(&env, &from, &spender);
+        let old_allowance = current.amount;
+        let unchanged = old_allowance == amount
+            && (amount == 0 || current.expiration_ledger == expiration_ledger)
+            && !Self::is_single_use(env.clone(), from.clone(), spender.clone());
+        
+        // 5. Actualizar allowance
+        Self::write_allowance(&env, &from, &spender, amount, expiration_ledger)?;
+        Self::clear_single_use(&env, &from, &spender);
+        
+        // 6. Evento mejorado con allowance anterior y nuevo
+        // (se omite si no cambió nada y SuppressNoopEvents está activo)
+        if !(unchanged && Self::suppress_noop_events(env.clone())) {
+            Self::publish(
+                &env,
+                (event_topic::APPROVE, from, spender),
+                (old_allowance, amount)
+            );
+        }
+        
+        Ok(())
+    }
+    
+    /// Descuenta `amount` del allowance `current` y devuelve el nuevo
+    /// 
+    /// Un allowance de un solo uso se revoca completo en el primer uso
+    /// (la key se elimina en 0). Con auto-extend, cada uso corre la
+    /// expiración (sin vencimiento no cambia)
+    fn spend_allowance(
+        env: &Env,
+        from: &Address,
+        spender: &Address,
+        current: &AllowanceValue,
+        amount: i128
+    ) -> Result<i128, TokenError> {
+        let new_allowance = if Self::is_single_use(env.clone(), from.clone(), spender.clone()) {
+            Self::clear_single_use(env, from, spender);
+            0
+        } else {
+            current.amount.checked_sub(amount)
+                .ok_or(TokenError::OverflowError)?
+        };
+        let expiration_ledger = current.expiration_ledger.saturating_add(
+            Self::allowance_auto_extend(env.clone(), from.clone(), spender.clone())
+        );
+        Self::write_allowance(env, from, spender, new_allowance, expiration_ledger)?;
+        Ok(new_allowance)
+    }
+    
+    /// Lógica de transfer_from() (ver report_failure)
+    fn execute_transfer_from(
+        env: Env, 
+        spender: Address, 
+        from: Address, 
+        to: Address, 
+        amount: i128
+    ) -> Result<(), TokenError> {
+        // 1. Verificar inicialización
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        
+        Self::require_not_paused(&env, PAUSE_TRANSFER_FROM)?;
+        
+        // 2. Verificar autorización del spender
+        spender.require_auth();
+        
+        // 3. Validaciones (amount > 0, sin auto-transferencia)
+        Self::validate_transfer(&env, &from, &to, amount)?;
+        
+        // Con NonTransferableAllowance el destino no puede ser un
+        // contrato operador que a su vez delega allowances
+        if Self::non_transferable_allowance(env.clone())
+            && Self::is_contract(&to)
+            && Self::allowance_count(env.clone(), to.clone()) > 0
+        {
+            return Err(TokenError::InvalidRecipient);
+        }
+        
+        // 4. Verificar allowance (expirado cuenta como 0)
+        let current = Self::read_allowance(&env, &from, &spender);
+        let allowed = current.amount;
+        if allowed < amount {
+            return Err(TokenError::InsufficientAllowance);
+        }
+        
+        if Self::require_spender_opt_in(env.clone())
+            && !Self::has_accepted_spending(env.clone(), spender.clone(), from.clone())
+        {
+            return Err(TokenError::SpenderNotOptedIn);
+        }
+        
+        // 5. Calcular balances (verifica saldo y aplica el fee)
+        // El allowance cubre todo lo debitado, incluido un fee a cargo del sender
+        let plan = Self::plan_transfer(&env, &from, &to, amount)?;
+        if allowed < plan.debit {
+            return Err(TokenError::InsufficientAllowance);
+        }
+        
+        // Por encima del umbral queda retenida como en transfer(); el
+        // allowance se consume igual (cancel_transfer no lo devuelve)
+        if Self::exceeds_delay_threshold(&env, amount) {
+            Self::hold_transfer(&env, &from, &to, amount, &plan)?;
+            Self::spend_allowance(&env, &from, &spender, &current, plan.debit)?;
+            return Ok(());
+        }
+        
+        // 6. Actualizar balances con TTL
+        Self::apply_transfer(&env, &from, &to, &plan);
+        Self::record_outgoing_transfer(&env, &from, amount)?;
+        
+        // 7. Actualizar allowance
+        let new_allowance = Self::spend_allowance(&env, &from, &spender, &current, plan.debit)?;
+        
+        // 8. Emitir evento completo (FIX: evento faltante)
+        let topics = (event_topic::TRANSFER_FROM, spender, from.clone(), to.clone());
+        match Self::next_sender_seq(&env, &from)? {
+            Some(seq) => Self::publish(
+                &env,
+                topics,
+                (amount, plan.new_from_balance, plan.new_to_balance, new_allowance, seq)
+            ),
+            None => Self::publish(
+                &env,
+                topics,
+                (amount, plan.new_from_balance, plan.new_to_balance, new_allowance)
+            ),
+        }
+        
+        Ok(())
+    }
+    
+    /// Lógica común de mint(), mint_by(), mint_vested(), migrate_from()
+    /// y mint_inflation() (el minter ya autorizó)
+    fn execute_mint(
+        env: Env,
+        minter: Address,
+        to: Address,
+        amount: i128
+    ) -> Result<(), TokenError> {
+        // 3. Validaciones
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        Self::require_whole_units(&env, amount)?;
+        
+        if !Self::allow_mint_to_frozen(env.clone()) {
+            Self::require_not_frozen(&env, &to)?;
+        }
+        
+        Self::require_holder_slot(&env, &to)?;
+        
+        // 4. Validar que `to` no sea igual a `admin` (opcional, pero buena práctica)
+        // Esto evita que el admin se mintee tokens a sí mismo por error
+        
+        // 5. Obtener balance actual y verificar overflow
+        let balance = Self::balance(env.clone(), to.clone());
+        let new_balance = balance.checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        
+        // 6. Actualizar balance con TTL extendido
+        Self::write_balance(&env, &to, new_balance);
+        Self::record_receipt(&env, &to);
+        
+        // 7. Actualizar total supply
+        let total: i128 = env.storage().instance()
+            .get(&DataKey::TotalSupply)
+            .unwrap_or(0);
+        let new_total = total.checked_add(amount)
+            .ok_or(TokenError::OverflowError)?;
+        if Self::max_supply(env.clone()).is_some_and(|cap| new_total > cap) {
+            return Err(TokenError::SupplyCapExceeded);
+        }
+        env.storage().instance().set(
+            &DataKey::TotalSupply, 
+            &new_total
+        );
+        Self::record_supply_checkpoint(&env, new_total);
+        
+        // 8. Emitir evento detallado (incluye quién autorizó el mint)
+        Self::publish(
+            &env,
+            (event_topic::MINT, to.clone()), 
+            (amount, new_balance, new_total, minter)
+        );
+        
+        Ok(())
+    }
+    
+    /// Emite el evento `fail` si `result` es un error y el flag está activo
+    /// 
+    /// Devuelve `result` sin cambios
+    fn report_failure<T>(
+        env: &Env,
+        operation: Symbol,
+        caller: Address,
+        result: Result<T, TokenError>
+    ) -> Result<T, TokenError> {
+        if let Err(error) = &result {
+            if Self::emit_failure_events(env.clone()) {
+                Self::publish(
+                    env,
+                    (event_topic::FAILURE, caller),
+                    (operation, *error as u32)
+                );
+            }
+        }
+        
+        result
+    }
+    
+    /// Lee la configuración vigente para validar un cambio
+    fn current_config(env: &Env) -> Result<ConfigCheck, TokenError> {
+        Ok(ConfigCheck {
+            admin: Self::admin(env.clone())?,
+            fee_bps: Self::transfer_fee_bps(env.clone()),
+            flat_fee: Self::flat_fee(env.clone()),
+            treasury: Self::treasury(env.clone()),
+            xlm_fee: Self::xlm_fee(env.clone()),
+            xlm_fee_collector: Self::xlm_fee_collector(env.clone()),
+        })
+    }
+    
+    /// Chequeos de coherencia centralizados de la configuración
+    /// 
+    /// Rechaza con InvalidConfig: bps fuera de rango, fee (bps o fijo)
+    /// sin treasury, fee fijo negativo,
+    /// treasury igual al admin o al propio contrato (loops de fees),
+    /// fee en XLM negativo y collector igual al propio contrato
+    fn validate_config(env: &Env, config: &ConfigCheck) -> Result<(), TokenError> {
+        let this = env.current_contract_address();
+        
+        if config.fee_bps > BPS_DENOMINATOR {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        match &config.treasury {
+            Some(treasury) => {
+                if *treasury == config.admin || *treasury == this {
+                    return Err(TokenError::InvalidConfig);
+                }
+            }
+            None => {
+                if config.fee_bps > 0 || config.flat_fee > 0 {
+                    return Err(TokenError::InvalidConfig);
+                }
+            }
+        }
+        
+        if config.flat_fee < 0 {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        if config.xlm_fee < 0 {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        if config.xlm_fee_collector.as_ref() == Some(&this) {
+            return Err(TokenError::InvalidConfig);
+        }
+        
+        Ok(())
+    }
+    
+    /// Publica un evento del contrato
+    /// 
+    /// Si hay un EventNamespace configurado se agrega como primer topic
+    /// y, con EventSeqEnabled, el número de secuencia como último
+    /// (todos los eventos del contrato pasan por acá)
+    // Los topics se arman en runtime (namespace y secuencia opcionales),
+    // algo que #[contractevent] no puede expresar
+    #[allow(deprecated)]
+    fn publish<T, D>(env: &Env, topics: T, data: D)
+    where
+        T: Topics,
+        D: IntoVal<Env, Val>,
+    {
+        let namespace: Option<Symbol> = env.storage().instance().get(&DataKey::EventNamespace);
+        let seq = Self::next_event_seq(env);
+        if namespace.is_none() && seq.is_none() {
+            env.events().publish(topics, data);
+            return;
+        }
+        
+        let mut topics: Vec<Val> = topics.into_val(env);
+        if let Some(namespace) = namespace {
+            topics.push_front(namespace.into_val(env));
+        }
+        if let Some(seq) = seq {
+            topics.push_back(seq.into_val(env));
+        }
+        env.events().publish(topics, data);
+    }
+    
+    /// Asigna el siguiente número de secuencia de eventos
+    /// 
+    /// None si la secuencia no se activó en initialize. Un evento de una
+    /// invocación fallida se descarta junto con su incremento, así que
+    /// los números publicados son contiguos
+    fn next_event_seq(env: &Env) -> Option<u64> {
+        if !env.storage().instance().has(&DataKeyExt::EventSeqEnabled) {
+            return None;
+        }
+        
+        let seq = Self::last_event_seq(env.clone())
+            .checked_add(1)
+            .unwrap_or_else(|| panic_with_error!(env, TokenError::OverflowError));
+        env.storage().instance().set(&DataKeyExt::EventSeq, &seq);
+        Some(seq)
+    }
+    
+    /// Indica si la dirección es de un contrato (strkey "C...")
+    fn is_contract(address: &Address) -> bool {
+        let mut strkey = [0u8; 56];
+        address.to_string().copy_into_slice(&mut strkey);
+        strkey[0] == b'C'
+    }
+    
+    /// Valida name y symbol (largos y, salvo `allow_any_symbol`, A-Z/0-9)
+    fn validate_metadata(
+        name: &String,
+        symbol: &String,
+        allow_any_symbol: bool
+    ) -> Result<(), TokenError> {
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(TokenError::InvalidMetadata);
+        }
+        
+        if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LENGTH {
+            return Err(TokenError::InvalidMetadata);
+        }
+        
+        if !allow_any_symbol && !Self::is_valid_symbol(symbol) {
+            return Err(TokenError::InvalidMetadata);
+        }
+        
+        Ok(())
+    }
+    
+    /// Indica si el symbol tiene solo mayúsculas y dígitos (A-Z, 0-9)
+    /// 
+    /// Asume que ya se validó el largo (<= MAX_SYMBOL_LENGTH)
+    fn is_valid_symbol(symbol: &String) -> bool {
+        let mut bytes = [0u8; MAX_SYMBOL_LENGTH as usize];
+        let len = symbol.len() as usize;
+        symbol.copy_into_slice(&mut bytes[..len]);
+        bytes[..len].iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    }
+    
+    /// Devuelve BatchTooLarge si el batch supera MAX_BATCH_SIZE
+    fn validate_batch_len(len: u32) -> Result<(), TokenError> {
+        if len > MAX_BATCH_SIZE {
+            return Err(TokenError::BatchTooLarge);
+        }
+        Ok(())
+    }
+    
+    /// Verifica inicialización y exige la firma del admin
+    /// 
+    /// Con multisig el admin no actúa solo (Unauthorized): lo que cubre
+    /// AdminAction se ejecuta vía propuesta y el resto queda fijo
+    fn require_admin(env: &Env) -> Result<Address, TokenError> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(TokenError::NotInitialized);
+        }
+        Self::require_no_multisig(env)?;
+        
+        let admin = Self::admin(env.clone())?;
+        admin.require_auth();
+        
+        Ok(admin)
+    }
+    
+    /// Igual que require_admin(), pero falla con ConfigLocked si la
+    /// configuración se bloqueó con lock_config() y con CooldownActive
+    /// si no pasó ConfigChangeCooldown desde el último cambio
+    /// 
+    /// Registra el ledger del cambio (si el setter falla después, la
+    /// escritura se revierte con el resto de la llamada)
+    fn require_config_admin(env: &Env) -> Result<Address, TokenError> {
+        let admin = Self::require_admin(env)?;
+        Self::require_config_change(env)?;
+        Ok(admin)
+    }
+    
+    /// Chequeos de require_config_admin() sin la auth del admin (los
+    /// usa también el multisig): ConfigLocked, CooldownActive y el
+    /// registro del ledger del cambio
+    fn require_config_change(env: &Env) -> Result<(), TokenError> {
+        if Self::config_locked(env.clone()) {
+            return Err(TokenError::ConfigLocked);
+        }
+        
+        let now = env.ledger().sequence();
+        let cooldown = Self::config_change_cooldown(env.clone());
+        if let Some(last) = Self::last_config_change(env.clone()) {
+            if cooldown > 0 && now < last.saturating_add(cooldown) {
+                return Err(TokenError::CooldownActive);
+            }
+        }
+        env.storage().instance().set(&DataKeyExt2::LastConfigChange, &now);
+        
+        Ok(())
+    }
+    
+    /// Devuelve quién autoriza mint() y mint_vested() tras exigir su auth
+    /// 
+    /// El mint controller si existe; si no, el admin. Con multisig el
+    /// admin no mintea directo (Unauthorized): se mintea vía propuesta
+    fn require_minter(env: &Env) -> Result<Address, TokenError> {
+        let minter = match Self::mint_controller(env.clone()) {
+            Some(controller) => controller,
+            None => {
+                Self::require_no_multisig(env)?;
+                Self::admin(env.clone())?
+            }
+        };
+        minter.require_auth();
+        Ok(minter)
+    }
+    
+    /// Devuelve Unauthorized si hay multisig: el admin ya no puede
+    /// actuar solo (ver require_admin)
+    fn require_no_multisig(env: &Env) -> Result<(), TokenError> {
+        if Self::multisig(env.clone()).is_some() {
+            return Err(TokenError::Unauthorized);
+        }
+        Ok(())
+    }
+    
+    /// Reconstruye las InitOptions a partir de los flags guardados
+    fn stored_options(env: &Env) -> InitOptions {
+        InitOptions {
+            community_pool: env.storage().instance()
+                .get(&DataKey::CommunityPoolEnabled)
+                .unwrap_or(false),
+            track_tx_count: env.storage().instance()
+                .get(&DataKey::TxCountEnabled)
+                .unwrap_or(false),
+            event_namespace: env.storage().instance()
+                .get(&DataKey::EventNamespace),
+            reserve: env.storage().instance()
+                .get(&DataKeyExt::Reserve),
+            guardian: Self::guardian(env.clone()),
+            event_seq: env.storage().instance()
+                .get(&DataKeyExt::EventSeqEnabled)
+                .unwrap_or(false),
+            sender_seq: env.storage().instance()
+                .get(&DataKeyExt::SenderSeqEnabled)
+                .unwrap_or(false),
+            allow_zero_decimals: false,
+            allow_any_symbol: false,
+            drain_enabled: Self::drain_enabled(env.clone()),
+            track_counterparties: env.storage().instance()
+                .has(&DataKeyExt::CounterpartiesEnabled),
+        }
+    }
+    
+    /// Indica si los parámetros coinciden con la inicialización existente
+    fn matches_init_params(
+        env: &Env,
+        admin: &Address,
+        name: &String,
+        symbol: &String,
+        decimals: u32,
+        options: &InitOptions
+    ) -> bool {
+        let storage = env.storage().instance();
+        storage.get::<DataKey, Address>(&DataKey::Admin).as_ref() == Some(admin)
+            && storage.get::<DataKey, String>(&DataKey::TokenName).as_ref() == Some(name)
+            && storage.get::<DataKey, String>(&DataKey::TokenSymbol).as_ref() == Some(symbol)
+            && storage.get::<DataKey, u32>(&DataKey::Decimals) == Some(decimals)
+            && Self::stored_options(env) == InitOptions {
+                allow_zero_decimals: false,
+                allow_any_symbol: false,
+                ..options.clone()
+            }
+    }
+    
+    /// Lee el allowance entre `from` y `spender`
+    /// 
+    /// Un allowance inexistente o expirado se devuelve con amount 0.
+    /// Sigue vigente durante los ledgers de AllowanceGrace
+    fn read_allowance(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        let valid_until = |value: &AllowanceValue| {
+            value.expiration_ledger.saturating_add(Self::allowance_grace(env.clone()))
+        };
+        let decimals = Self::decimals(env.clone());
+        match env.storage().persistent().get::<DataKey, AllowanceValue>(&key) {
+            Some(value) if valid_until(&value) >= env.ledger().sequence() => AllowanceValue {
+                amount: Self::rescale(env, value.amount, value.decimals),
+                expiration_ledger: value.expiration_ledger,
+                decimals,
+            },
+            Some(value) => AllowanceValue {
+                amount: 0,
+                expiration_ledger: value.expiration_ledger,
+                decimals,
+            },
+            None => AllowanceValue {
+                amount: 0,
+                expiration_ledger: 0,
+                decimals,
+            },
+        }
+    }
+    
+    /// Devuelve InvalidRecipient si `spender` es el propio contrato y
+    /// BlockSelfApprove está activo
+    /// 
+    /// Un allowance de 0 (revocar) siempre se permite
+    fn require_not_self_spender(env: &Env, spender: &Address, amount: i128) -> Result<(), TokenError> {
+        if amount > 0
+            && *spender == env.current_contract_address()
+            && Self::block_self_approve(env.clone())
+        {
+            return Err(TokenError::InvalidRecipient);
+        }
+        Ok(())
+    }
+    
+    /// Devuelve AllowanceCapExceeded si `amount` supera el tope que
+    /// `from` fijó para `spender`
+    fn check_allowance_cap(
+        env: &Env,
+        from: &Address,
+        spender: &Address,
+        amount: i128
+    ) -> Result<(), TokenError> {
+        match Self::allowance_cap(env.clone(), from.clone(), spender.clone()) {
+            Some(cap) if amount > cap => Err(TokenError::AllowanceCapExceeded),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Escribe el allowance entre `from` y `spender`
+    /// 
+    /// Optimización: elimina la key si el amount es 0.
+    /// Mantiene AllowanceCount(from) y aplica MaxAllowancesPerOwner
+    /// cuando se crea una key nueva
+    fn write_allowance(
+        env: &Env,
+        from: &Address,
+        spender: &Address,
+        amount: i128,
+        expiration_ledger: u32
+    ) -> Result<(), TokenError> {
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        let exists = env.storage().persistent().has(&key);
+        let count = Self::allowance_count(env.clone(), from.clone());
+        
+        if amount == 0 {
+            env.storage().persistent().remove(&key);
+            if exists {
+                Self::write_allowance_count(env, from, count - 1);
+            }
+        } else {
+            if !exists {
+                let max = Self::max_allowances_per_owner(env.clone());
+                if max > 0 && count >= max {
+                    return Err(TokenError::TooManyAllowances);
+                }
+                let new_count = count.checked_add(1)
+                    .ok_or(TokenError::OverflowError)?;
+                Self::write_allowance_count(env, from, new_count);
+            }
+            
+            env.storage().persistent().set(
+                &key,
+                &AllowanceValue {
+                    amount,
+                    expiration_ledger,
+                    decimals: Self::decimals(env.clone()),
+                }
+            );
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
+        
+        Ok(())
+    }
+    
+    /// Guarda el balance de `account` con TTL extendido
+    /// 
+    /// Optimización: elimina la key si el balance es 0.
+    /// Antes de escribir guarda el checkpoint del snapshot vigente
+    /// y liquida los rewards acumulados con el balance anterior
+    fn write_balance(env: &Env, account: &Address, balance: i128) {
+        Self::checkpoint_balance(env, account);
+        Self::accrue_rewards(env, account);
+        
+        let key = DataKey::Balance(account.clone());
+        let warnings = Self::near_expiry_warnings(env.clone());
+        let extend_to = Self::balance_extend_to(env, account);
+        if balance == 0 {
+            env.storage().persistent().remove(&key);
+            if warnings {
+                env.storage().persistent().remove(&DataKeyExt::BalanceLiveUntil(account.clone()));
+            }
+        } else {
+            if warnings {
+                Self::track_balance_ttl(env, account, extend_to);
+            }
+            env.storage().persistent().set(&key, &balance);
+            env.storage().persistent().extend_ttl(&key, extend_to / 2, extend_to);
+            Self::register_holder(env, account);
+        }
+    }
+    
+    /// Estima el TTL del balance de `account` antes de extenderlo
+    /// 
+    /// Replica extend_ttl(extend_to / 2, extend_to): solo extiende si
+    /// quedaban menos de extend_to / 2 ledgers. Emite near_exp si
+    /// quedaban menos de NEAR_EXPIRY_LEDGERS
+    fn track_balance_ttl(env: &Env, account: &Address, extend_to: u32) {
+        let key = DataKeyExt::BalanceLiveUntil(account.clone());
+        let now = env.ledger().sequence();
+        let mut live_until = now.saturating_add(extend_to);
+        
+        let previous: Option<u32> = env.storage().persistent().get(&key);
+        if let Some(previous) = previous {
+            let remaining = previous.saturating_sub(now);
+            if remaining < NEAR_EXPIRY_LEDGERS {
+                Self::publish(
+                    env,
+                    (event_topic::NEAR_EXPIRY, account.clone()),
+                    remaining
+                );
+            }
+            if remaining >= extend_to / 2 {
+                live_until = previous;
+            }
+        }
+        
+        env.storage().persistent().set(&key, &live_until);
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+    }
+    
+    /// Ledgers a los que se extiende el TTL del balance de `account`
+    /// 
+    /// BALANCE_TTL_LEDGERS sin TtlTiers; con niveles, el activo si la
+    /// cuenta envió tokens dentro de la ventana y el base si no
+    fn balance_extend_to(env: &Env, account: &Address) -> u32 {
+        let tiers = match Self::ttl_tiers(env.clone()) {
+            Some(tiers) => tiers,
+            None => return BALANCE_TTL_LEDGERS,
+        };
+        
+        let last_sent: Option<u32> = env.storage().persistent()
+            .get(&DataKeyExt2::LastSent(account.clone()));
+        let now = env.ledger().sequence();
+        if last_sent.is_some_and(|ledger| now.saturating_sub(ledger) < tiers.active_window) {
+            tiers.active_extend_to
+        } else {
+            tiers.baseline_extend_to
+        }
+    }
+    
+    /// Registra que `from` envió tokens en este ledger (solo con TtlTiers)
+    /// 
+    /// Se llama antes de escribir los balances, así el envío actual ya
+    /// cuenta como actividad
+    fn record_sent(env: &Env, from: &Address) {
+        if !env.storage().instance().has(&DataKeyExt2::TtlTiers) {
+            return;
+        }
+        
+        let key = DataKeyExt2::LastSent(from.clone());
+        env.storage().persistent().set(&key, &env.ledger().sequence());
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+    }
+    
+    /// Devuelve HolderCapReached si acreditar a `account` lo agregaría
+    /// al registro de holders por encima de MaxHolders
+    fn require_holder_slot(env: &Env, account: &Address) -> Result<(), TokenError> {
+        let max = Self::max_holders(env.clone());
+        if max > 0
+            && Self::holder_count(env.clone()) >= max
+            && !env.storage().persistent().has(&DataKey::IsHolder(account.clone()))
+        {
+            return Err(TokenError::HolderCapReached);
+        }
+        Ok(())
+    }
+    
+    /// Agrega `account` al final del registro de holders si no está
+    fn register_holder(env: &Env, account: &Address) {
+        let marker = DataKey::IsHolder(account.clone());
+        if env.storage().persistent().has(&marker) {
+            return;
+        }
+        
+        let index = Self::holder_count(env.clone());
+        let key = DataKey::Holder(index);
+        env.storage().persistent().set(&key, account);
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        env.storage().persistent().set(&marker, &true);
+        env.storage().persistent().extend_ttl(&marker, 100_000, 200_000);
+        env.storage().instance().set(&DataKey::HolderCount, &(index + 1));
+    }
+    
+    /// Guarda el balance bloqueado de `account` (elimina la key en 0)
+    fn write_locked(env: &Env, account: &Address, locked: i128) {
+        let key = DataKey::Locked(account.clone());
+        if locked == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &locked);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
+    }
+    
+    /// Guarda el calendario de vesting de `account` (elimina la key si queda vacío)
+    fn write_vest_schedule(env: &Env, account: &Address, schedule: &Vec<VestTranche>) {
+        let key = DataKeyExt::VestSchedule(account.clone());
+        if schedule.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, schedule);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
+    }
+    
+    /// Quema `amount` del balance no bloqueado de `from` (burn, burn_from y burn_redeem)
+    /// 
+    /// Valida freeze, cooldown y protección; no pide autorización.
+    /// Devuelve (nuevo balance, nuevo supply)
+    fn burn_balance(env: &Env, from: &Address, amount: i128) -> Result<(i128, i128), TokenError> {
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        Self::require_whole_units(env, amount)?;
+        
+        Self::require_not_frozen(env, from)?;
+        Self::require_no_cooldown(env, from)?;
+        Self::require_not_protected(env, from)?;
+        
+        // Solo se puede quemar el balance no bloqueado
+        let balance = Self::balance(env.clone(), from.clone());
+        if Self::spendable_balance(env.clone(), from.clone()) < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        // Elimina la key si queda en 0
+        let new_balance = balance.checked_sub(amount)
+            .ok_or(TokenError::OverflowError)?;
+        Self::write_balance(env, from, new_balance);
+        let new_total = Self::reduce_supply(env, amount)?;
+        
+        Ok((new_balance, new_total))
+    }
+    
+    /// Descuenta `amount` del supply total después de un burn
+    /// 
+    /// Graba el checkpoint de supply y, en modo sink, acredita lo
+    /// quemado al pool comunitario. Devuelve el nuevo supply.
+    /// Con RequireNonEmpty falla si el supply quedaría en 0
+    fn reduce_supply(env: &Env, amount: i128) -> Result<i128, TokenError> {
+        let new_total = Self::total_supply(env.clone())
+            .checked_sub(amount)
+            .ok_or(TokenError::OverflowError)?;
+        if new_total == 0 && Self::require_non_empty(env.clone()) {
+            return Err(TokenError::CannotRemoveLastHolder);
+        }
+        env.storage().instance().set(&DataKey::TotalSupply, &new_total);
+        Self::record_supply_checkpoint(env, new_total);
+        
+        // Modo sink: acreditar lo quemado al pool comunitario
+        if env.storage().instance()
+            .get(&DataKey::CommunityPoolEnabled)
+            .unwrap_or(false)
+        {
+            let pool = Self::community_pool(env.clone());
+            let new_pool = pool.checked_add(amount)
+                .ok_or(TokenError::OverflowError)?;
+            env.storage().instance().set(&DataKey::CommunityPool, &new_pool);
+        }
+        
+        Ok(new_total)
+    }
+    
+    /// Guarda el balance previo de `account` para el snapshot vigente
+    /// 
+    /// Se llama antes de cada cambio de balance; solo escribe en el
+    /// primer cambio de la cuenta desde que se declaró el snapshot
+    fn checkpoint_balance(env: &Env, account: &Address) {
+        let id = Self::current_snapshot_id(env.clone());
+        if id == 0 {
+            return;
+        }
+        
+        let ids_key = DataKey::BalanceCheckpointIds(account.clone());
+        let mut ids: Vec<u64> = env.storage().persistent()
+            .get(&ids_key)
+            .unwrap_or(Vec::new(env));
+        if ids.last() == Some(id) {
+            return;
+        }
+        
+        let key = DataKey::LastBalanceCheckpoint(account.clone(), id);
+        let balance = Self::balance(env.clone(), account.clone());
+        env.storage().persistent().set(&key, &balance);
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        
+        ids.push_back(id);
+        env.storage().persistent().set(&ids_key, &ids);
+        env.storage().persistent().extend_ttl(&ids_key, 100_000, 200_000);
+    }
+    
+    /// Liquida los rewards de `account` hasta el ledger actual
+    /// 
+    /// Se llama antes de cada cambio de balance, con el balance previo
+    /// todavía guardado. Mientras nunca hubo rate (índice 0) no escribe nada
+    fn accrue_rewards(env: &Env, account: &Address) {
+        let index = Self::reward_index(env);
+        let paid = Self::reward_index_paid(env, account);
+        if index == paid {
+            return;
+        }
+        
+        let earned = Self::unsettled_rewards(env, account);
+        if earned > 0 {
+            let key = DataKeyExt2::AccruedRewards(account.clone());
+            let accrued: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            env.storage().persistent().set(&key, &accrued.saturating_add(earned));
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
+        
+        let key = DataKeyExt2::RewardIndexPaid(account.clone());
+        env.storage().persistent().set(&key, &index);
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+    }
+    
+    /// Rewards de `account` desde su última liquidación (redondeo hacia abajo)
+    fn unsettled_rewards(env: &Env, account: &Address) -> i128 {
+        let delta = Self::reward_index(env) - Self::reward_index_paid(env, account);
+        Self::balance(env.clone(), account.clone()).saturating_mul(delta) / REWARD_RATE_SCALE
+    }
+    
+    /// RewardIndex al ledger actual: el guardado más rate × ledgers
+    /// transcurridos desde RewardIndexLedger
+    fn reward_index(env: &Env) -> i128 {
+        let index: i128 = env.storage().instance()
+            .get(&DataKeyExt2::RewardIndex)
+            .unwrap_or(0);
+        let rate = Self::reward_rate(env.clone());
+        if rate == 0 {
+            return index;
+        }
+        
+        let since: u32 = env.storage().instance()
+            .get(&DataKeyExt2::RewardIndexLedger)
+            .unwrap_or(0);
+        let elapsed = env.ledger().sequence().saturating_sub(since);
+        index.saturating_add((rate as i128) * (elapsed as i128))
+    }
+    
+    /// Índice con el que se liquidó `account` (0 si nunca se liquidó)
+    fn reward_index_paid(env: &Env, account: &Address) -> i128 {
+        env.storage().persistent()
+            .get(&DataKeyExt2::RewardIndexPaid(account.clone()))
+            .unwrap_or(0)
+    }
+    
+    /// Divide `amount` en (fee, neto) según el fee y redondeo vigentes
+    /// 
+    /// Primero se descuenta el fee fijo y el de bps se calcula sobre el
+    /// resto. Garantiza fee + neto == amount. Sin treasury no hay fee.
+    /// Con fee fijo, amount <= fee fijo falla con AmountTooSmall
+    fn split_fee(env: &Env, amount: i128) -> Result<(i128, i128), TokenError> {
+        if Self::treasury(env.clone()).is_none() {
+            return Ok((0, amount));
+        }
+        
+        // 1. Fee fijo
+        let flat = Self::flat_fee(env.clone());
+        if flat > 0 && amount <= flat {
+            return Err(TokenError::AmountTooSmall);
+        }
+        let remainder = amount.checked_sub(flat)
+            .ok_or(TokenError::OverflowError)?;
+        
+        // 2. Fee en bps sobre el resto
+        let bps = Self::transfer_fee_bps(env.clone());
+        let scaled = remainder.checked_mul(bps as i128)
+            .ok_or(TokenError::OverflowError)?;
+        let denominator = BPS_DENOMINATOR as i128;
+        let bps_fee = match Self::rounding_mode(env.clone()) {
+            RoundingMode::Floor => scaled / denominator,
+            RoundingMode::Ceil => {
+                let floor = scaled / denominator;
+                if scaled % denominator == 0 { floor } else { floor + 1 }
+            }
+        };
+        let bps_fee = Self::floor_whole_units(env, bps_fee);
+        
+        let fee = flat.checked_add(bps_fee)
+            .ok_or(TokenError::OverflowError)?;
+        let net = amount.checked_sub(fee)
+            .ok_or(TokenError::OverflowError)?;
+        Ok((fee, net))
+    }
+    
+    /// Reparte `fee` entre sus receptores
+    /// 
+    /// Con FeeConfig, cada receptor recibe floor(fee * bps / 10000) y el
+    /// último además el resto, así no se pierde valor. Sin FeeConfig
+    /// todo va al treasury
+    fn fee_shares(env: &Env, fee: i128) -> Result<Vec<(Address, i128)>, TokenError> {
+        let recipients = Self::fee_recipients(env.clone());
+        if recipients.is_empty() {
+            let treasury = Self::treasury(env.clone()).ok_or(TokenError::InvalidConfig)?;
+            return Ok(Vec::from_array(env, [(treasury, fee)]));
+        }
+        
+        let mut shares = Vec::new(env);
+        let mut remaining = fee;
+        let last = recipients.len() - 1;
+        for (index, (recipient, bps)) in recipients.iter().enumerate() {
+            let share = if index as u32 == last {
+                remaining
+            } else {
+                Self::floor_whole_units(
+                    env,
+                    fee.checked_mul(bps as i128).ok_or(TokenError::OverflowError)?
+                        / BPS_DENOMINATOR as i128
+                )
+            };
+            remaining -= share;
+            shares.push_back((recipient, share));
+        }
+        
+        Ok(shares)
+    }
+    
+    /// Divide `amount` en (enteros, fracción) según los decimales del token
+    /// 
+    /// Con decimals == 0 la fracción siempre es 0
+    fn split_decimals(env: &Env, amount: i128) -> (i128, u64) {
+        // decimals <= MAX_DECIMALS (18), así que 10^decimals entra en i128
+        let unit = 10i128.pow(Self::decimals(env.clone()));
+        (amount / unit, (amount % unit) as u64)
+    }
+    
+    /// Validaciones comunes a toda transferencia
+    /// 
+    /// amount > 0, sin auto-transferencia y sender no congelado
+    fn validate_transfer(
+        env: &Env,
+        from: &Address,
+        to: &Address,
+        amount: i128
+    ) -> Result<(), TokenError> {
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        // No permitir transferencia a sí mismo (gas-efficient)
+        if from == to {
+            return Err(TokenError::InvalidRecipient);
+        }
+        
+        Self::require_not_frozen(env, from)?;
+        Self::require_launch_route(env, from, to)?;
+        
+        if Self::restricts_destinations(env.clone(), from.clone())
+            && !Self::is_destination_approved(env.clone(), from.clone(), to.clone())
+        {
+            return Err(TokenError::DestinationNotApproved);
+        }
+        
+        Self::require_whole_units(env, amount)?;
+        Self::check_volume(env, from, amount)?;
+        Self::require_price_condition(env)?;
+        
+        Ok(())
+    }
+    
+    /// Con WholeUnitsOnly, devuelve FractionalAmount si `amount` no es
+    /// múltiplo de 10^decimals
+    fn require_whole_units(env: &Env, amount: i128) -> Result<(), TokenError> {
+        if !Self::whole_units_only(env.clone()) {
+            return Ok(());
+        }
+        
+        let unit = 10i128.pow(Self::decimals(env.clone()));
+        if amount % unit != 0 {
+            return Err(TokenError::FractionalAmount);
+        }
+        Ok(())
+    }
+    
+    /// Con WholeUnitsOnly, redondea hacia abajo a un múltiplo de
+    /// 10^decimals un monto derivado (fees, rewards, inflación)
+    fn floor_whole_units(env: &Env, amount: i128) -> i128 {
+        if !Self::whole_units_only(env.clone()) {
+            return amount;
+        }
+        
+        let unit = 10i128.pow(Self::decimals(env.clone()));
+        amount - amount % unit
+    }
+    
+    /// Con una PriceCondition configurada, consulta `get_price()` en el
+    /// oráculo y devuelve ConditionNotMet si el precio no la cumple
+    /// 
+    /// Un oráculo que falla o no devuelve un i128 también bloquea
+    fn require_price_condition(env: &Env) -> Result<(), TokenError> {
+        let condition = match Self::price_condition(env.clone()) {
+            Some(condition) => condition,
+            None => return Ok(()),
+        };
+        
+        let price = match env.try_invoke_contract::<i128, soroban_sdk::Error>(
+            &condition.oracle,
+            &Symbol::new(env, "get_price"),
+            Vec::new(env)
+        ) {
+            Ok(Ok(price)) => price,
+            _ => return Err(TokenError::ConditionNotMet),
+        };
+        
+        let met = match condition.direction {
+            PriceDirection::Above => price > condition.threshold,
+            PriceDirection::Below => price < condition.threshold,
+        };
+        if !met {
+            return Err(TokenError::ConditionNotMet);
+        }
+        Ok(())
+    }
+    
+    /// Devuelve Paused si el contrato está pausado y la política
+    /// vigente bloquea `operation` (uno de los bits PAUSE_*)
+    fn require_not_paused(env: &Env, operation: u32) -> Result<(), TokenError> {
+        if Self::is_paused(env.clone()) && Self::pause_policy(env.clone()) & operation != 0 {
+            return Err(TokenError::Paused);
+        }
+        Ok(())
+    }
+    
+    /// Durante el launch guard, devuelve Unauthorized si ni `from` ni
+    /// `to` son un router habilitado o el admin
+    fn require_launch_route(env: &Env, from: &Address, to: &Address) -> Result<(), TokenError> {
+        if env.ledger().sequence() >= Self::launch_guard_until(env.clone()) {
+            return Ok(());
+        }
         
-        // 8. Emitir evento detallado
-        env.events().publish(
-            (symbol_short!("mint"), to.clone()), 
-            (amount, new_balance, new_total)
-        );
+        let admin = Self::admin(env.clone())?;
+        if *from == admin || *to == admin
+            || Self::is_launch_router(env.clone(), from.clone())
+            || Self::is_launch_router(env.clone(), to.clone())
+        {
+            return Ok(());
+        }
         
+        Err(TokenError::Unauthorized)
+    }
+    
+    /// Devuelve AccountFrozen si la cuenta está congelada
+    fn require_not_frozen(env: &Env, account: &Address) -> Result<(), TokenError> {
+        if Self::is_frozen(env.clone(), account.clone()) {
+            return Err(TokenError::AccountFrozen);
+        }
         Ok(())
     }
     
-    fn burn(env: Env, from: Address, amount: i128) -> Result<(), TokenError> {
-        // 1. Verificar inicialización
+    /// Aprueba o quita un destino de `owner` (requiere auth del owner)
+    fn set_destination(
+        env: &Env,
+        owner: Address,
+        destination: Address,
+        approved: bool
+    ) -> Result<(), TokenError> {
         if !env.storage().instance().has(&DataKey::Initialized) {
             return Err(TokenError::NotInitialized);
         }
         
-        // 2. Requiere autorización del dueño de los tokens
-        from.require_auth();
+        owner.require_auth();
         
-        // 3. Validaciones
-        if amount <= 0 {
-            return Err(TokenError::InvalidAmount);
+        let key = DataKeyExt::ApprovedDest(owner.clone(), destination.clone());
+        if approved {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        } else {
+            env.storage().persistent().remove(&key);
         }
         
-        let balance = Self::balance(env.clone(), from.clone());
-        if balance < amount {
-            return Err(TokenError::InsufficientBalance);
-        }
+        Self::publish(env, (event_topic::DESTINATION, owner, destination), approved);
         
-        // 4. Actualizar balance
-        let new_balance = balance - amount;
-        if new_balance == 0 {
-            // Optimización: eliminar key si balance = 0
-            env.storage().persistent().remove(&DataKey::Balance(from.clone()));
+        Ok(())
+    }
+    
+    /// Otorga o quita un rol y emite el evento
+    fn set_role(env: &Env, role: Role, account: &Address, granted: bool) {
+        let key = DataKeyExt::RoleMember(role, account.clone());
+        if granted {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+            Self::publish(env, (event_topic::GRANT_ROLE, role, account.clone()), ());
         } else {
-            env.storage().persistent().set(
-                &DataKey::Balance(from.clone()),
-                &new_balance
-            );
-            env.storage().persistent().extend_ttl(
-                &DataKey::Balance(from.clone()),
-                100_000,
-                200_000
-            );
+            env.storage().persistent().remove(&key);
+            Self::publish(env, (event_topic::REVOKE_ROLE, role, account.clone()), ());
+        }
+    }
+    
+    /// Valida una lista de cuentas de initialize_with_roles() o de
+    /// firmantes del multisig
+    /// 
+    /// Máximo MAX_INITIAL_ROLE_MEMBERS y sin repetidos (InvalidConfig)
+    fn validate_role_members(members: &Vec<Address>) -> Result<(), TokenError> {
+        if members.len() > MAX_INITIAL_ROLE_MEMBERS {
+            return Err(TokenError::BatchTooLarge);
         }
         
-        // 5. Actualizar total supply
-        let total: i128 = env.storage().instance()
-            .get(&DataKey::TotalSupply)
-            .unwrap_or(0);
-        let new_total = total.checked_sub(amount)
-            .ok_or(TokenError::OverflowError)?;
-        env.storage().instance().set(
-            &DataKey::TotalSupply,
-            &new_total
-        );
-        
-        // 6. Emitir evento
-        env.events().publish(
-            (symbol_short!("burn"), from),
-            (amount, new_balance, new_total)
-        );
+        for (i, member) in members.iter().enumerate() {
+            if members.iter().skip(i + 1).any(|other| other == member) {
+                return Err(TokenError::InvalidConfig);
+            }
+        }
         
         Ok(())
     }
     
-    fn balance(env: Env, account: Address) -> i128 {
-        env.storage().persistent()
-            .get(&DataKey::Balance(account))
-            .unwrap_or(0)
+    /// Valida los firmantes y el umbral del multisig
+    /// 
+    /// Requiere 1 <= threshold <= cantidad de firmantes (InvalidConfig)
+    fn validate_multisig(multisig: &Multisig) -> Result<(), TokenError> {
+        Self::validate_role_members(&multisig.signers)?;
+        if multisig.threshold == 0 || multisig.threshold > multisig.signers.len() {
+            return Err(TokenError::InvalidConfig);
+        }
+        Ok(())
     }
     
-    fn transfer(
-        env: Env, 
-        from: Address, 
-        to: Address, 
-        amount: i128
-    ) -> Result<(), TokenError> {
-        // 1. Verificar inicialización
-        if !env.storage().instance().has(&DataKey::Initialized) {
-            return Err(TokenError::NotInitialized);
+    /// Devuelve Unauthorized si `account` no es firmante del multisig
+    /// (InvalidConfig si no hay multisig) y pide su autorización
+    fn require_signer(env: &Env, account: &Address) -> Result<Multisig, TokenError> {
+        let multisig = Self::multisig(env.clone()).ok_or(TokenError::InvalidConfig)?;
+        if !multisig.signers.contains(account) {
+            return Err(TokenError::Unauthorized);
+        }
+        account.require_auth();
+        Ok(multisig)
+    }
+    
+    /// Ejecuta la acción de una propuesta que alcanzó el threshold
+    /// 
+    /// Aplica las mismas reglas que la función equivalente del admin,
+    /// salvo la autorización (ya la dieron los firmantes)
+    fn execute_action(env: &Env, action: AdminAction) -> Result<(), TokenError> {
+        let contract = env.current_contract_address();
+        match action {
+            AdminAction::Mint(to, amount) => {
+                Self::require_not_paused(env, PAUSE_MINT)?;
+                // Con mint controller, también hace falta su auth
+                let minter = match Self::mint_controller(env.clone()) {
+                    Some(controller) => {
+                        controller.require_auth();
+                        controller
+                    }
+                    None => contract,
+                };
+                Self::execute_mint(env.clone(), minter, to, amount)?;
+            }
+            AdminAction::Pause => {
+                env.storage().instance().set(&DataKey::Paused, &true);
+                Self::publish(
+                    env,
+                    (event_topic::PAUSE, contract),
+                    Self::pause_policy(env.clone())
+                );
+            }
+            AdminAction::Unpause => {
+                env.storage().instance().remove(&DataKey::Paused);
+                Self::publish(env, (event_topic::UNPAUSE, contract), ());
+            }
+            AdminAction::SetTransferFee(bps, treasury) => {
+                Self::require_config_change(env)?;
+                Self::write_transfer_fee(env, bps, treasury)?;
+            }
+            AdminAction::Upgrade(wasm_hash) => {
+                // El código nuevo podría deshacer lock_config: cuenta como cambio de config
+                Self::require_config_change(env)?;
+                env.deployer().update_current_contract_wasm(wasm_hash);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Ejecuta la propuesta si alcanzó `threshold`; si no, la guarda
+    /// 
+    /// Devuelve true si se ejecutó (y la borra)
+    fn advance_proposal(
+        env: &Env,
+        id: u64,
+        proposal: Proposal,
+        threshold: u32
+    ) -> Result<bool, TokenError> {
+        let key = DataKeyExt2::Proposal(id);
+        if proposal.approvals.len() < threshold {
+            env.storage().persistent().set(&key, &proposal);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+            return Ok(false);
         }
         
-        // 2. Verificar autorización del sender
-        from.require_auth();
+        env.storage().persistent().remove(&key);
+        Self::execute_action(env, proposal.action)?;
+        Self::publish(env, (event_topic::EXECUTE_PROPOSAL, id), proposal.approvals);
         
-        // 3. Validaciones
-        if amount <= 0 {
-            return Err(TokenError::InvalidAmount);
+        Ok(true)
+    }
+    
+    /// Valida y guarda el fee de transferencia (set_transfer_fee y multisig)
+    fn write_transfer_fee(env: &Env, bps: u32, treasury: Address) -> Result<(), TokenError> {
+        let mut config = Self::current_config(env)?;
+        config.fee_bps = bps;
+        config.treasury = Some(treasury.clone());
+        Self::validate_config(env, &config)?;
+        
+        env.storage().instance().set(&DataKey::TransferFeeBps, &bps);
+        env.storage().instance().set(&DataKey::Treasury, &treasury);
+        
+        Self::publish(
+            env,
+            (event_topic::CONFIG, Symbol::new(env, "transfer_fee")),
+            (bps, treasury)
+        );
+        
+        Ok(())
+    }
+    
+    /// Devuelve ProtectedAccount si la cuenta está protegida contra burns
+    fn require_not_protected(env: &Env, account: &Address) -> Result<(), TokenError> {
+        if Self::is_protected(env.clone(), account.clone()) {
+            return Err(TokenError::ProtectedAccount);
+        }
+        Ok(())
+    }
+    
+    /// Marca o desmarca una cuenta como congelada y emite el evento
+    fn set_frozen(env: &Env, account: &Address, frozen: bool) {
+        let key = DataKey::Frozen(account.clone());
+        if frozen {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+            Self::publish(env, (event_topic::FREEZE, account.clone()), true);
+        } else {
+            env.storage().persistent().remove(&key);
+            Self::publish(env, (event_topic::UNFREEZE, account.clone()), false);
         }
+    }
+    
+    /// Calcula el resultado de mover `amount` de `from` a `to` sin escribir
+    /// 
+    /// Verifica el saldo y aplica el fee según FeePayer: con Recipient
+    /// `to` recibe amount - fee; con SenderPaysExtra se debita
+    /// amount + fee a `from` y `to` recibe amount. El fee se reparte
+    /// entre sus receptores (ver fee_shares; contempla que un receptor
+    /// sea una de las partes). Si `to` no recibiría nada devuelve
+    /// AmountTooSmall
+    fn plan_transfer(
+        env: &Env,
+        from: &Address,
+        to: &Address,
+        amount: i128
+    ) -> Result<TransferPlan, TokenError> {
+        let (fee, net) = Self::split_fee(env, amount)?;
+        let (debit, credit) = match Self::fee_payer(env.clone()) {
+            FeePayer::Recipient => (amount, net),
+            FeePayer::SenderPaysExtra => (
+                amount.checked_add(fee).ok_or(TokenError::OverflowError)?,
+                amount
+            ),
+        };
         
-        // 4. No permitir transferencia a sí mismo (gas-efficient)
-        if from == to {
-            return Err(TokenError::InvalidRecipient);
+        // Un fee que absorbe todo el monto no cuenta como transferencia
+        if credit == 0 {
+            return Err(TokenError::AmountTooSmall);
         }
         
+        // El balance bloqueado no se puede transferir
         let from_balance = Self::balance(env.clone(), from.clone());
-        if from_balance < amount {
+        if Self::spendable_balance(env.clone(), from.clone()) < debit {
             return Err(TokenError::InsufficientBalance);
         }
         
-        // 5. Calcular nuevos balances con verificación de overflow
-        let new_from_balance = from_balance - amount;
-        let to_balance = Self::balance(env.clone(), to.clone());
-        let new_to_balance = to_balance.checked_add(amount)
+        // Un `to` (o treasury) nuevo ocupa un lugar en el registro de holders
+        Self::require_holder_slot(env, to)?;
+        
+        let mut new_from_balance = from_balance.checked_sub(debit)
+            .ok_or(TokenError::OverflowError)?;
+        let mut new_to_balance = Self::balance(env.clone(), to.clone())
+            .checked_add(credit)
             .ok_or(TokenError::OverflowError)?;
         
-        // 6. Actualizar balances con TTL
-        // Optimización: si from_balance = 0, eliminar key
-        if new_from_balance == 0 {
-            env.storage().persistent().remove(&DataKey::Balance(from.clone()));
-        } else {
-            env.storage().persistent().set(
-                &DataKey::Balance(from.clone()),
-                &new_from_balance
-            );
-            env.storage().persistent().extend_ttl(
-                &DataKey::Balance(from.clone()),
-                100_000,
-                200_000
-            );
+        let mut fee_credits = Vec::new(env);
+        if fee > 0 {
+            for (address, share) in Self::fee_shares(env, fee)?.iter() {
+                if share == 0 {
+                    continue;
+                }
+                
+                Self::require_holder_slot(env, &address)?;
+                let base = if address == *to {
+                    new_to_balance
+                } else if address == *from {
+                    new_from_balance
+                } else {
+                    Self::balance(env.clone(), address.clone())
+                };
+                let new_balance = base.checked_add(share)
+                    .ok_or(TokenError::OverflowError)?;
+                
+                if address == *to {
+                    new_to_balance = new_balance;
+                } else if address == *from {
+                    new_from_balance = new_balance;
+                }
+                fee_credits.push_back((address, share, new_balance));
+            }
         }
         
-        env.storage().persistent().set(
-            &DataKey::Balance(to.clone()),
-            &new_to_balance
-        );
-        env.storage().persistent().extend_ttl(
-            &DataKey::Balance(to.clone()),
-            100_000,
-            200_000
-        );
-        
-        // 7. Emitir evento con balances post-transferencia
-        env.events().publish(
-            (symbol_short!("transfer"), from, to), 
-            (amount, new_from_balance, new_to_balance)
-        );
+        // Sin polvo: vaciar la cuenta o dejar al menos el mínimo
+        let min = Self::min_account_balance(env.clone());
+        if new_from_balance > 0 && new_from_balance < min {
+            return Err(TokenError::BelowMinimum);
+        }
         
-        Ok(())
+        Ok(TransferPlan {
+            debit,
+            new_from_balance,
+            new_to_balance,
+            fee,
+            fee_credits,
+        })
     }
     
-    fn approve(
-        env: Env, 
-        from: Address, 
-        spender: Address, 
-        amount: i128
-    ) -> Result<(), TokenError> {
-        // 1. Verificar inicialización
-        if !env.storage().instance().has(&DataKey::Initialized) {
-            return Err(TokenError::NotInitialized);
+    /// Escribe los balances calculados por plan_transfer()
+    fn apply_transfer(env: &Env, from: &Address, to: &Address, plan: &TransferPlan) {
+        Self::record_sent(env, from);
+        Self::write_balance(env, from, plan.new_from_balance);
+        Self::write_balance(env, to, plan.new_to_balance);
+        Self::record_receipt(env, to);
+        Self::record_counterparties(env, from, to);
+        
+        for (recipient, share, new_balance) in plan.fee_credits.iter() {
+            Self::write_balance(env, &recipient, new_balance);
+            Self::record_receipt(env, &recipient);
+            
+            Self::publish(
+                env,
+                (event_topic::FEE, from.clone(), recipient),
+                (share, new_balance)
+            );
         }
+    }
+    
+    /// Indica si `amount` supera DelayThreshold (y debe quedar retenido)
+    fn exceeds_delay_threshold(env: &Env, amount: i128) -> bool {
+        let threshold = Self::delay_threshold(env.clone());
+        threshold > 0 && amount > threshold
+    }
+    
+    /// Descuenta `plan.debit` de `from` y guarda la transferencia como
+    /// pendiente; `to` y los receptores del fee cobran en settle_transfer
+    /// 
+    /// Devuelve el id de la pendiente y el nuevo balance de `from`
+    fn hold_transfer(
+        env: &Env,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+        plan: &TransferPlan
+    ) -> Result<(u64, i128), TokenError> {
+        let new_from_balance = Self::balance(env.clone(), from.clone())
+            .checked_sub(plan.debit)
+            .ok_or(TokenError::OverflowError)?;
+        Self::record_sent(env, from);
+        Self::write_balance(env, from, new_from_balance);
+        Self::record_outgoing_transfer(env, from, amount)?;
         
-        // 2. Verificar autorización del owner
-        from.require_auth();
+        let id: u64 = env.storage().instance()
+            .get(&DataKeyExt::NextPendingId)
+            .unwrap_or(0);
+        env.storage().instance().set(&DataKeyExt::NextPendingId, &(id + 1));
         
-        // 3. Validación: amount debe ser >= 0 (permitir 0 para revocar)
-        if amount < 0 {
-            return Err(TokenError::InvalidAmount);
-        }
+        let settle_ledger = env.ledger().sequence()
+            .saturating_add(Self::settlement_delay(env.clone()));
+        let key = DataKeyExt::PendingTransfer(id);
+        let credit = plan.debit.checked_sub(plan.fee)
+            .ok_or(TokenError::OverflowError)?;
+        env.storage().persistent().set(&key, &PendingTransfer {
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+            debit: plan.debit,
+            credit,
+            settle_ledger,
+        });
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
         
-        // 4. Obtener allowance anterior para el evento
-        let old_allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        Self::publish(
+            env,
+            (event_topic::PENDING, from.clone(), to.clone()),
+            (id, amount, new_from_balance, settle_ledger)
+        );
         
-        // 5. Actualizar allowance
-        if amount == 0 {
-            // Optimización: eliminar key si allowance = 0
-            env.storage().persistent().remove(
-                &DataKey::Allowance(from.clone(), spender.clone())
-            );
-        } else {
-            env.storage().persistent().set(
-                &DataKey::Allowance(from.clone(), spender.clone()),
-                &amount
-            );
-            env.storage().persistent().extend_ttl(
-                &DataKey::Allowance(from.clone(), spender.clone()),
-                100_000,
-                200_000
-            );
+        Ok((id, new_from_balance))
+    }
+    
+    /// Reparte un fee ya descontado a `payer` entre sus receptores
+    fn distribute_fee(env: &Env, payer: &Address, fee: i128) -> Result<(), TokenError> {
+        if fee <= 0 {
+            return Ok(());
         }
         
-        // 6. Evento mejorado con allowance anterior y nuevo
-        env.events().publish(
-            (symbol_short!("approve"), from, spender),
-            (old_allowance, amount)
-        );
+        for (recipient, share) in Self::fee_shares(env, fee)?.iter() {
+            if share == 0 {
+                continue;
+            }
+            
+            let new_balance = Self::balance(env.clone(), recipient.clone())
+                .checked_add(share)
+                .ok_or(TokenError::OverflowError)?;
+            Self::write_balance(env, &recipient, new_balance);
+            Self::record_receipt(env, &recipient);
+            
+            Self::publish(
+                env,
+                (event_topic::FEE, payer.clone(), recipient),
+                (share, new_balance)
+            );
+        }
         
         Ok(())
     }
     
-    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
-        env.storage().persistent()
-            .get(&DataKey::Allowance(from, spender))
-            .unwrap_or(0)
+    /// Guarda `to` como último destino de `from` y `from` como último
+    /// origen de `to`
+    /// 
+    /// Solo escribe si se activó track_counterparties en initialize
+    fn record_counterparties(env: &Env, from: &Address, to: &Address) {
+        if !env.storage().instance().has(&DataKeyExt::CounterpartiesEnabled) {
+            return;
+        }
+        
+        let mut sender = Self::last_counterparty(env.clone(), from.clone());
+        sender.sent_to = Some(to.clone());
+        let mut recipient = Self::last_counterparty(env.clone(), to.clone());
+        recipient.received_from = Some(from.clone());
+        
+        for (account, value) in [(from, sender), (to, recipient)] {
+            let key = DataKeyExt::LastCounterparty(account.clone());
+            env.storage().persistent().set(&key, &value);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
     }
     
-    fn transfer_from(
-        env: Env, 
-        spender: Address, 
-        from: Address, 
-        to: Address, 
-        amount: i128
-    ) -> Result<(), TokenError> {
-        // 1. Verificar inicialización
-        if !env.storage().instance().has(&DataKey::Initialized) {
-            return Err(TokenError::NotInitialized);
+    /// Registra el ledger en que `account` recibió tokens
+    /// 
+    /// Solo escribe si hay un ActionCooldown configurado
+    fn record_receipt(env: &Env, account: &Address) {
+        if Self::action_cooldown(env.clone()) == 0 {
+            return;
         }
         
-        // 2. Verificar autorización del spender
-        spender.require_auth();
+        let key = DataKey::LastAction(account.clone());
+        env.storage().persistent().set(&key, &env.ledger().sequence());
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+    }
+    
+    /// Devuelve CooldownActive si `account` recibió tokens hace menos
+    /// de ActionCooldown ledgers
+    fn require_no_cooldown(env: &Env, account: &Address) -> Result<(), TokenError> {
+        let cooldown = Self::action_cooldown(env.clone());
+        if cooldown == 0 {
+            return Ok(());
+        }
         
-        // 3. Validaciones
-        if amount <= 0 {
-            return Err(TokenError::InvalidAmount);
+        let last: Option<u32> = env.storage().persistent()
+            .get(&DataKey::LastAction(account.clone()));
+        if let Some(last) = last {
+            if env.ledger().sequence() < last.saturating_add(cooldown) {
+                return Err(TokenError::CooldownActive);
+            }
         }
         
-        // 4. No permitir transferencia a sí mismo
-        if from == to {
-            return Err(TokenError::InvalidRecipient);
+        Ok(())
+    }
+    
+    /// Índice de la ventana de volumen que contiene el ledger actual
+    fn current_volume_window(env: &Env) -> u32 {
+        env.ledger().sequence() / Self::volume_window(env.clone())
+    }
+    
+    /// Devuelve VolumeLimitExceeded si `amount` supera el volumen que
+    /// le queda a `from` en la ventana actual
+    fn check_volume(env: &Env, from: &Address, amount: i128) -> Result<(), TokenError> {
+        match Self::volume_remaining(env.clone(), from.clone()) {
+            Some(remaining) if amount > remaining => Err(TokenError::VolumeLimitExceeded),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Registra una transferencia saliente de `from`
+    /// 
+    /// Suma `amount` al volumen de la ventana si la cuenta tiene límite
+    /// (y tx_count si está activo)
+    fn record_outgoing_transfer(env: &Env, from: &Address, amount: i128) -> Result<(), TokenError> {
+        if Self::volume_limit(env.clone(), from.clone()).is_some() {
+            Self::check_volume(env, from, amount)?;
+            
+            let window_ledgers = Self::volume_window(env.clone());
+            let window = Self::current_volume_window(env);
+            let key = DataKeyExt::VolumeUsed(from.clone(), window);
+            let used = Self::volume_used(env.clone(), from.clone())
+                .checked_add(amount)
+                .ok_or(TokenError::OverflowError)?;
+            // La entrada solo tiene que vivir hasta el fin de su ventana
+            let live_for = (window + 1)
+                .saturating_mul(window_ledgers)
+                .saturating_sub(env.ledger().sequence());
+            env.storage().temporary().set(&key, &used);
+            env.storage().temporary().extend_ttl(&key, live_for, live_for);
         }
         
-        // 5. Verificar allowance
-        let allowed = Self::allowance(env.clone(), from.clone(), spender.clone());
-        if allowed < amount {
-            return Err(TokenError::InsufficientAllowance);
+        if env.storage().instance()
+            .get(&DataKey::TxCountEnabled)
+            .unwrap_or(false)
+        {
+            let key = DataKey::TxCount(from.clone());
+            let count = Self::tx_count(env.clone(), from.clone())
+                .checked_add(1)
+                .ok_or(TokenError::OverflowError)?;
+            env.storage().persistent().set(&key, &count);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
         }
         
-        // 6. Verificar balance
-        let from_balance = Self::balance(env.clone(), from.clone());
-        if from_balance < amount {
-            return Err(TokenError::InsufficientBalance);
+        Ok(())
+    }
+    
+    /// Asigna la siguiente secuencia de transfer saliente de `from`
+    /// 
+    /// None si la secuencia por sender no se activó en initialize
+    fn next_sender_seq(env: &Env, from: &Address) -> Result<Option<u64>, TokenError> {
+        if !env.storage().instance().has(&DataKeyExt::SenderSeqEnabled) {
+            return Ok(None);
         }
         
-        // 7. Calcular nuevos valores
-        let new_from_balance = from_balance - amount;
-        let to_balance = Self::balance(env.clone(), to.clone());
-        let new_to_balance = to_balance.checked_add(amount)
+        let key = DataKeyExt::SenderSeq(from.clone());
+        let seq = Self::sender_seq(env.clone(), from.clone())
+            .checked_add(1)
             .ok_or(TokenError::OverflowError)?;
-        let new_allowance = allowed - amount;
+        env.storage().persistent().set(&key, &seq);
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
         
-        // 8. Actualizar estado atómicamente
-        // Optimización: eliminar keys si son 0
-        if new_from_balance == 0 {
-            env.storage().persistent().remove(&DataKey::Balance(from.clone()));
-        } else {
-            env.storage().persistent().set(
-                &DataKey::Balance(from.clone()),
-                &new_from_balance
-            );
-            env.storage().persistent().extend_ttl(
-                &DataKey::Balance(from.clone()),
-                100_000,
-                200_000
-            );
+        Ok(Some(seq))
+    }
+    
+    /// Configuración del fee en XLM: (SAC nativo, collector, fee)
+    /// 
+    /// None si el fee es 0 o no hay collector configurado
+    fn xlm_fee_route(env: &Env) -> Option<(Address, Address, i128)> {
+        let fee = Self::xlm_fee(env.clone());
+        let collector = Self::xlm_fee_collector(env.clone())?;
+        if fee <= 0 {
+            return None;
         }
+        let native: Address = env.storage().instance().get(&DataKey::NativeToken)?;
+        Some((native, collector, fee))
+    }
+    
+    /// Cobra el fee en XLM de `from` al collector vía el SAC nativo
+    /// 
+    /// No hace nada si el fee es 0 o no hay collector configurado
+    fn charge_xlm_fee(env: &Env, from: &Address) -> Result<(), TokenError> {
+        let (native, collector, fee) = match Self::xlm_fee_route(env) {
+            Some(route) => route,
+            None => return Ok(()),
+        };
         
-        env.storage().persistent().set(
-            &DataKey::Balance(to.clone()),
-            &new_to_balance
-        );
-        env.storage().persistent().extend_ttl(
-            &DataKey::Balance(to.clone()),
-            100_000,
-            200_000
-        );
-        
-        if new_allowance == 0 {
-            env.storage().persistent().remove(
-                &DataKey::Allowance(from.clone(), spender.clone())
-            );
-        } else {
-            env.storage().persistent().set(
-                &DataKey::Allowance(from.clone(), spender.clone()),
-                &new_allowance
-            );
-            env.storage().persistent().extend_ttl(
-                &DataKey::Allowance(from.clone(), spender.clone()),
-                100_000,
-                200_000
-            );
+        let paid = token::Client::new(env, &native)
+            .try_transfer(from, &collector, &fee);
+        if !matches!(paid, Ok(Ok(()))) {
+            return Err(TokenError::FeePaymentFailed);
         }
         
-        // 9. Emitir evento completo (FIX: evento faltante)
-        env.events().publish(
-            (symbol_short!("trnsf_frm"), spender, from.clone(), to.clone()),
-            (amount, new_from_balance, new_to_balance, new_allowance)
-        );
-        
         Ok(())
     }
     
-    // Métodos de consulta
-    fn name(env: Env) -> String {
-        // Verificar inicialización antes de devolver metadata
-        if !env.storage().instance().has(&DataKey::Initialized) {
-            return String::from_str(&env, "");
-        }
+    /// Verifica, sin mover fondos, que `from` puede pagar el fee en XLM
+    fn check_xlm_fee(env: &Env, from: &Address) -> Result<(), TokenError> {
+        let (native, _, fee) = match Self::xlm_fee_route(env) {
+            Some(route) => route,
+            None => return Ok(()),
+        };
         
-        env.storage().instance()
-            .get(&DataKey::TokenName)
-            .unwrap_or(String::from_str(&env, ""))
+        match token::Client::new(env, &native).try_balance(from) {
+            Ok(Ok(balance)) if balance >= fee => Ok(()),
+            _ => Err(TokenError::FeePaymentFailed),
+        }
     }
     
-    fn symbol(env: Env) -> String {
-        if !env.storage().instance().has(&DataKey::Initialized) {
-            return String::from_str(&env, "");
+    /// Graba el supply actual como checkpoint del ledger en curso
+    /// 
+    /// Varias operaciones en un mismo ledger comparten checkpoint y,
+    /// superado MAX_SUPPLY_CHECKPOINTS, se elimina el más viejo
+    fn record_supply_checkpoint(env: &Env, supply: i128) {
+        let ledger = env.ledger().sequence();
+        let (mut start, end): (u32, u32) = env.storage().instance()
+            .get(&DataKey::SupplyCheckpointRange)
+            .unwrap_or((0, 0));
+        
+        if end > start {
+            let last_key = DataKey::SupplyCheckpoint(end - 1);
+            let last: Option<(u32, i128)> = env.storage().persistent().get(&last_key);
+            if let Some((last_ledger, _)) = last {
+                if last_ledger == ledger {
+                    env.storage().persistent().set(&last_key, &(ledger, supply));
+                    env.storage().persistent().extend_ttl(&last_key, 100_000, 200_000);
+                    return;
+                }
+            }
         }
         
-        env.storage().instance()
-            .get(&DataKey::TokenSymbol)
-            .unwrap_or(String::from_str(&env, ""))
+        let key = DataKey::SupplyCheckpoint(end);
+        env.storage().persistent().set(&key, &(ledger, supply));
+        env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        
+        let end = end + 1;
+        if end - start > MAX_SUPPLY_CHECKPOINTS {
+            env.storage().persistent().remove(&DataKey::SupplyCheckpoint(start));
+            start += 1;
+        }
+        env.storage().instance().set(&DataKey::SupplyCheckpointRange, &(start, end));
     }
     
-    fn decimals(env: Env) -> u32 {
-        if !env.storage().instance().has(&DataKey::Initialized) {
-            return 0;
-        }
-        
-        env.storage().instance()
-            .get(&DataKey::Decimals)
-            .unwrap_or(0)
+    /// Quita la marca de uso único entre `from` y `spender`
+    fn clear_single_use(env: &Env, from: &Address, spender: &Address) {
+        env.storage().persistent().remove(&DataKey::SingleUse(from.clone(), spender.clone()));
     }
     
-    fn total_supply(env: Env) -> i128 {
-        env.storage().instance()
-            .get(&DataKey::TotalSupply)
-            .unwrap_or(0)
+    /// Lleva `amount`, guardado con `decimals`, a los decimales actuales
+    /// 
+    /// migrate_decimals() no recorre las entradas por par de cuentas
+    /// (allowances, topes, pools, límites de volumen): se reescalan al
+    /// leerlas. Satura en i128::MAX (un allowance "infinito" sigue siéndolo)
+    fn rescale(env: &Env, amount: i128, decimals: u32) -> i128 {
+        let current = Self::decimals(env.clone());
+        if current <= decimals {
+            return amount;
+        }
+        amount.saturating_mul(10i128.pow(current - decimals))
     }
     
-    fn admin(env: Env) -> Address {
-        env.storage().instance()
-            .get(&DataKey::Admin)
-            .expect("Admin not initialized")
+    /// Guarda el contador de allowances de `owner` (elimina la key en 0)
+    fn write_allowance_count(env: &Env, owner: &Address, count: u32) {
+        let key = DataKey::AllowanceCount(owner.clone());
+        if count == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &count);
+            env.storage().persistent().extend_ttl(&key, 100_000, 200_000);
+        }
     }
 }
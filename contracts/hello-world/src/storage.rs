@@ -1,5 +1,5 @@
 // src/storage.rs
-use soroban_sdk::{contracttype, Address, String};
+use soroban_sdk::{contracttype, Address, BytesN, String, Symbol, Vec};
 
 /// Enum que define todas las claves de almacenamiento
 /// 
@@ -14,6 +14,7 @@ pub enum DataKey {
     
     /// Permisos de gasto entre usuarios - Persistent Storage
     /// Tupla (owner, spender) para lookup eficiente
+    /// Guarda un AllowanceValue (amount + ledger de expiración)
     Allowance(Address, Address),
     
     /// Supply total de tokens - Instance Storage
@@ -39,6 +40,393 @@ pub enum DataKey {
     /// Flag para verificar inicialización - Instance Storage
     /// Previene re-inicialización del contrato
     Initialized,
+    
+    /// Flag del modo "sink" del pool comunitario - Instance Storage
+    /// Si está activo, cada burn acredita el contador CommunityPool
+    CommunityPoolEnabled,
+    
+    /// Contador acumulado del pool comunitario - Instance Storage
+    /// Overlay contable: no representa tokens reales
+    CommunityPool,
+    
+    /// Máximo de allowances distintos por owner - Instance Storage
+    /// 0 (o ausente) significa ilimitado
+    MaxAllowancesPerOwner,
+    
+    /// Cantidad de allowances activos de cada owner - Persistent Storage
+    /// Se elimina la key cuando llega a 0
+    AllowanceCount(Address),
+    
+    /// Contrato SAC del activo nativo (XLM) - Instance Storage
+    /// Se usa para cobrar el fee de transferencia en XLM
+    NativeToken,
+    
+    /// Receptor del fee en XLM - Instance Storage
+    /// Sin collector el cobro está desactivado
+    XlmFeeCollector,
+    
+    /// Fee en stroops de XLM por transfer - Instance Storage
+    /// 0 (o ausente) desactiva el cobro
+    XlmFee,
+    
+    /// Checkpoint histórico del supply - Persistent Storage
+    /// Índice -> (ledger, total_supply) tras cada mint/burn
+    SupplyCheckpoint(u32),
+    
+    /// Rango de checkpoints retenidos - Instance Storage
+    /// (primer índice, siguiente índice libre)
+    SupplyCheckpointRange,
+    
+    /// Fee de transferencia en basis points - Instance Storage
+    /// Se descuenta del amount y se acredita al Treasury
+    TransferFeeBps,
+    
+    /// Receptor del fee de transferencia - Instance Storage
+    Treasury,
+    
+    /// Dirección de redondeo del fee - Instance Storage
+    /// Floor por defecto
+    RoundingMode,
+    
+    /// Marca de allowance de un solo uso - Persistent Storage
+    /// Tupla (owner, spender): el primer transfer_from revoca el resto
+    SingleUse(Address, Address),
+    
+    /// Marca de cuenta congelada - Persistent Storage
+    /// Solo existe la key mientras la cuenta está congelada
+    Frozen(Address),
+    
+    /// Flag del contador de transferencias - Instance Storage
+    /// Se activa en initialize (InitOptions::track_tx_count)
+    TxCountEnabled,
+    
+    /// Transferencias salientes de cada cuenta - Persistent Storage
+    /// Incluye transfer y transfer_from (cuenta el `from`)
+    TxCount(Address),
+    
+    /// Namespace de eventos del deployment - Instance Storage
+    /// Si existe, es el primer topic de todos los eventos
+    EventNamespace,
+    
+    /// Flag de contrato pausado - Instance Storage
+    /// Solo existe la key mientras está pausado
+    Paused,
+    
+    /// Operaciones bloqueadas durante la pausa - Instance Storage
+    /// Bitmask de PAUSE_* (por defecto PAUSE_ALL)
+    PausePolicy,
+    
+    /// Contrato controlador de minteo - Instance Storage
+    /// Si existe, mint() exige su auth en lugar de la del admin
+    MintController,
+    
+    /// Permite mintear a cuentas congeladas - Instance Storage
+    /// Por defecto false: mint a una cuenta congelada falla
+    AllowMintToFrozen,
+    
+    /// Operación de transfer_idempotent ya procesada - Temporary Storage
+    /// (from, op_id); expira sola tras OP_ID_TTL ledgers
+    ProcessedOp(Address, BytesN<32>),
+    
+    /// Id del último snapshot declarado - Instance Storage
+    SnapshotId,
+    
+    /// Balance de la cuenta al declararse el snapshot - Persistent Storage
+    /// Se escribe en el primer cambio de balance posterior al snapshot
+    LastBalanceCheckpoint(Address, u64),
+    
+    /// Ids de snapshot con checkpoint de la cuenta - Persistent Storage
+    /// Ordenados de forma creciente (búsqueda binaria)
+    BalanceCheckpointIds(Address),
+    
+    /// Flag de opt-in obligatorio del spender - Instance Storage
+    /// Si está activo, transfer_from exige SpenderOptIn
+    RequireSpenderOptIn,
+    
+    /// El spender acepta allowances de `from` - Persistent Storage
+    /// (spender, from)
+    SpenderOptIn(Address, Address),
+    
+    /// Flag de eventos de operaciones fallidas - Instance Storage
+    EmitFailureEvents,
+    
+    /// Parte bloqueada del balance - Persistent Storage
+    /// Incluida en Balance(Address), pero no transferible
+    Locked(Address),
+    
+    /// Balance mínimo que debe dejar una transferencia - Instance Storage
+    /// 0 (o ausente) lo desactiva
+    MinAccountBalance,
+    
+    /// Quién soporta el fee de transferencia - Instance Storage
+    /// Ver FeePayer (Recipient por defecto)
+    FeePayer,
+    
+    /// Cantidad de holders registrados - Instance Storage
+    HolderCount,
+    
+    /// Holder en la posición dada (orden de alta) - Persistent Storage
+    /// Registro append-only: las posiciones nunca cambian
+    Holder(u32),
+    
+    /// Marca de cuenta ya registrada como holder - Persistent Storage
+    IsHolder(Address),
+    
+    /// Ledgers de cooldown de burn tras recibir tokens - Instance Storage
+    /// 0 (o ausente) lo desactiva
+    ActionCooldown,
+    
+    /// Último ledger en que la cuenta recibió tokens - Persistent Storage
+    /// Solo se escribe con ActionCooldown activo
+    LastAction(Address),
+    
+    /// Estado de la migración de decimales - Instance Storage
+    /// Ver MigrationStatus (Disabled si no existe)
+    DecimalsMigration,
+}
+
+/// Continuación de DataKey para las claves nuevas
+/// 
+/// La spec de contratos admite hasta 50 variantes por enum y DataKey
+/// ya está en el límite. Los nombres de variantes no deben repetirse
+/// con los de DataKey (la key serializada es el nombre + sus campos)
+#[contracttype]
+pub enum DataKeyExt {
+    /// Flag de avisos de expiración de balances - Instance Storage
+    NearExpiryWarnings,
+    
+    /// Ledger estimado hasta el que vive el balance - Persistent Storage
+    /// Solo se mantiene con NearExpiryWarnings activo
+    BalanceLiveUntil(Address),
+    
+    /// Cuenta reserva de la que sale distribute() - Instance Storage
+    /// Se configura en initialize (InitOptions::reserve)
+    Reserve,
+    
+    /// Tope de allowance fijado por el owner - Persistent Storage
+    /// (owner, spender); sin la key no hay tope. Guarda (tope, decimals)
+    AllowanceCap(Address, Address),
+    
+    /// Guardian que puede pausar (pero no despausar) - Instance Storage
+    /// Se configura en initialize (InitOptions::guardian) o con set_guardian
+    Guardian,
+    
+    /// Pago registrado por transfer_from_tracked - Persistent Storage
+    /// ref_id -> PaymentRecord (se conserva tras el refund)
+    PaymentRef(BytesN<32>),
+    
+    /// Máximo de holders registrados - Instance Storage
+    /// 0 (o ausente) significa ilimitado
+    MaxHolders,
+    
+    /// Marca de rol otorgado a una cuenta - Persistent Storage
+    /// Solo existe la key mientras el rol está otorgado
+    RoleMember(Role, Address),
+    
+    /// Flag del número de secuencia de eventos - Instance Storage
+    /// Se activa en initialize (InitOptions::event_seq)
+    EventSeqEnabled,
+    
+    /// Último número de secuencia asignado a un evento - Instance Storage
+    EventSeq,
+    
+    /// Tramos de vesting pendientes de la cuenta - Persistent Storage
+    /// Vec<VestTranche>; se elimina la key al reclamar el último
+    VestSchedule(Address),
+    
+    /// Marca de cuenta protegida contra burns - Persistent Storage
+    /// Solo existe la key mientras la cuenta está protegida
+    Protected(Address),
+    
+    /// Flag de la secuencia por sender en eventos de transfer - Instance Storage
+    /// Se activa en initialize (InitOptions::sender_seq)
+    SenderSeqEnabled,
+    
+    /// Última secuencia de transfer saliente de la cuenta - Persistent Storage
+    SenderSeq(Address),
+    
+    /// Receptores del fee de transferencia - Instance Storage
+    /// Vec<(Address, u32)> con partes en bps que suman 10000
+    FeeConfig,
+    
+    /// Fee fijo por transferencia (se cobra antes que el de bps) - Instance Storage
+    FlatFee,
+    
+    /// Ledger hasta el que rige el launch guard (exclusivo) - Instance Storage
+    LaunchGuardUntil,
+    
+    /// Router habilitado durante el launch guard - Persistent Storage
+    LaunchRouter(Address),
+    
+    /// Token viejo aceptado por migrate_from() - Instance Storage
+    LegacyToken,
+    
+    /// Tokens nuevos por token viejo migrado, en bps (10000 = 1:1) - Instance Storage
+    MigrationRate,
+    
+    /// Supply máximo que se puede mintear - Instance Storage
+    MaxSupply,
+    
+    /// Pool de gasto de `from` para `spender` (from, spender) - Persistent Storage
+    Pool(Address, Address),
+    
+    /// Omitir eventos de operaciones sin cambios - Instance Storage
+    SuppressNoopEvents,
+    
+    /// Programa de inflación (InflationConfig) - Instance Storage
+    Inflation,
+    
+    /// Inicio de la ventana de inflación en curso - Instance Storage
+    LastInflationLedger,
+    
+    /// Cuenta habilitada para buyback_burn() - Instance Storage
+    BuybackExecutor,
+    
+    /// Total quemado del treasury con buyback_burn() - Instance Storage
+    TotalBuybackBurned,
+    
+    /// Destino aprobado por un owner (owner, destino) - Persistent Storage
+    ApprovedDest(Address, Address),
+    
+    /// Owner que solo envía a destinos aprobados - Persistent Storage
+    RestrictDestinations(Address),
+    
+    /// Ledgers de gracia tras la expiración de un allowance - Instance Storage
+    /// 0 (o ausente) mantiene la expiración exacta
+    AllowanceGrace,
+    
+    /// Contrato relay notificado después de cada transfer - Instance Storage
+    /// Solo observa: si falla, la transferencia no se revierte
+    NotificationRelay,
+    
+    /// Volumen saliente máximo por ventana de una cuenta - Persistent Storage
+    /// Tiene prioridad sobre DefaultVolumeLimit. Guarda (límite, decimals)
+    VolumeLimit(Address),
+    
+    /// Volumen saliente máximo por ventana para todas las cuentas - Instance Storage
+    DefaultVolumeLimit,
+    
+    /// Largo en ledgers de la ventana de volumen - Instance Storage
+    VolumeWindow,
+    
+    /// Volumen saliente de una cuenta en una ventana - Temporary Storage
+    /// Vive hasta el fin de su ventana
+    VolumeUsed(Address, u32),
+    
+    /// Flag de drain_reserve habilitado en initialize - Instance Storage
+    DrainEnabled,
+    
+    /// Flag que impide quemar hasta dejar el token sin holders - Instance Storage
+    RequireNonEmpty,
+    
+    /// Flag que impide que transfer_from envíe a un contrato que delega
+    /// allowances propios - Instance Storage
+    NonTransferableAllowance,
+    
+    /// Flag irreversible de configuración bloqueada - Instance Storage
+    ConfigLocked,
+    
+    /// Flag de registro de contrapartes - Instance Storage
+    CounterpartiesEnabled,
+    
+    /// Última contraparte de cada cuenta - Persistent Storage
+    /// Solo se escribe con track_counterparties
+    LastCounterparty(Address),
+    
+    /// Monto a partir del cual transfer() queda pendiente - Instance Storage
+    DelayThreshold,
+    
+    /// Ledgers de espera de una transferencia pendiente - Instance Storage
+    SettlementDelay,
+    
+    /// Próximo id de transferencia pendiente - Instance Storage
+    NextPendingId,
+    
+    /// Transferencia pendiente por id - Persistent Storage
+    /// Se borra al liquidarla o cancelarla
+    PendingTransfer(u64),
+    
+    /// Activo subyacente que entrega burn_redeem - Instance Storage
+    RedemptionAsset,
+    
+    /// Subyacente por token redimido en bps (10000 = 1:1) - Instance Storage
+    RedemptionRate,
+    
+    /// Total quemado con burn_redeem - Instance Storage
+    TotalRedeemed,
+    
+    /// Flag que rechaza approves al propio contrato - Instance Storage
+    /// Se activa en initialize; los deploys anteriores no lo tienen
+    BlockSelfApprove,
+}
+
+/// Segunda continuación de las claves (DataKeyExt también llegó a las
+/// 50 variantes). Misma regla: sin nombres repetidos entre los tres enums
+#[contracttype]
+pub enum DataKeyExt2 {
+    /// Firmantes del multisig - Instance Storage
+    Signers,
+    
+    /// Aprobaciones requeridas por propuesta - Instance Storage
+    Threshold,
+    
+    /// Próximo id de propuesta - Instance Storage
+    NextProposalId,
+    
+    /// Propuesta del multisig por id - Persistent Storage
+    /// Se borra al ejecutarse
+    Proposal(u64),
+    
+    /// Niveles de TTL de los balances - Instance Storage
+    TtlTiers,
+    
+    /// Ledger del último envío de cada cuenta - Persistent Storage
+    /// Solo se escribe con TtlTiers configurado
+    LastSent(Address),
+    
+    /// Ledgers mínimos entre dos cambios de configuración - Instance Storage
+    ConfigChangeCooldown,
+    
+    /// Ledger del último cambio de configuración - Instance Storage
+    LastConfigChange,
+    
+    /// Oráculo de precio que condiciona las transferencias - Instance Storage
+    /// Guarda un PriceCondition; sin la key las transferencias no se condicionan
+    PriceOracle,
+    
+    /// Rewards por ledger y por unidad de balance, escalado por
+    /// REWARD_RATE_SCALE - Instance Storage
+    RewardRate,
+    
+    /// Rewards acumulados por unidad de balance desde el primer rate,
+    /// escalado por REWARD_RATE_SCALE - Instance Storage
+    RewardIndex,
+    
+    /// Ledger hasta el que está calculado RewardIndex - Instance Storage
+    RewardIndexLedger,
+    
+    /// RewardIndex con el que se liquidó la cuenta por última vez - Persistent Storage
+    /// Sin la key la cuenta no cambió de balance desde que hay rewards (índice 0)
+    RewardIndexPaid(Address),
+    
+    /// Rewards liquidados y no reclamados de cada cuenta - Persistent Storage
+    AccruedRewards(Address),
+    
+    /// Ledgers que cada transfer_from extiende el allowance - Persistent Storage
+    /// (owner, spender); sin la key la expiración no cambia
+    AutoExtendAllowance(Address, Address),
+    
+    /// Ledger en que se llamó a initialize - Instance Storage
+    DeployLedger,
+    
+    /// Flag: montos solo en unidades enteras (múltiplos de 10^decimals) - Instance Storage
+    WholeUnitsOnly,
+    
+    /// Penalidad en bps por desbloqueo anticipado - Instance Storage
+    EarlyUnlockPenalty,
+    
+    /// Total quemado como penalidad de emergency_unlock() - Instance Storage
+    TotalPenaltyBurned,
 }
 
 /// Metadata struct para almacenar información del token
@@ -49,4 +437,375 @@ pub struct TokenMetadata {
     pub name: String,
     pub symbol: String,
     pub decimals: u32,
-}
\ No newline at end of file
+}
+
+/// Valor almacenado para cada allowance
+/// 
+/// El allowance deja de ser válido cuando el ledger actual
+/// supera `expiration_ledger` (se lee como 0). `amount` está expresado
+/// con `decimals`: se reescala al leerlo si después se migraron
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+    pub decimals: u32,
+}
+
+/// Programa de inflación configurado con set_inflation()
+/// 
+/// Cada `window_ledgers` ledgers, mint_inflation() mintea
+/// total_supply * rate_bps / 10000 a `recipient`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InflationConfig {
+    pub rate_bps: u32,
+    pub window_ledgers: u32,
+    pub recipient: Address,
+}
+
+/// Pool de gasto abierto con open_pool()
+/// 
+/// A diferencia de un allowance, se consume con pull() y sus eventos
+/// van por separado. Vencido (ledger actual > `expiration_ledger`)
+/// se lee como 0. Los montos van con `decimals`, como en AllowanceValue
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendingPool {
+    pub total: i128,
+    pub remaining: i128,
+    pub expiration_ledger: u32,
+    pub decimals: u32,
+}
+
+/// Opciones de configuración aceptadas por initialize_with_options()
+/// 
+/// Todas las funcionalidades opcionales arrancan desactivadas
+/// (`InitOptions::default()`)
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InitOptions {
+    /// Cada burn incrementa el contador del pool comunitario
+    pub community_pool: bool,
+    
+    /// Cuenta las transferencias salientes de cada cuenta (tx_count)
+    pub track_tx_count: bool,
+    
+    /// Namespace agregado como primer topic de cada evento
+    pub event_namespace: Option<Symbol>,
+    
+    /// Cuenta con el supply pre-minteado que reparte distribute()
+    pub reserve: Option<Address>,
+    
+    /// Cuenta de baja privilegio que solo puede pausar el contrato
+    pub guardian: Option<Address>,
+    
+    /// Agrega un número de secuencia como último topic de cada evento
+    pub event_seq: bool,
+    
+    /// Agrega a los eventos de transfer una secuencia por sender
+    pub sender_seq: bool,
+    
+    /// Permite decimals = 0 (rechazado por default: sorprende a muchas
+    /// integraciones). Solo valida, no se guarda
+    pub allow_zero_decimals: bool,
+    
+    /// Omite la validación del symbol (solo A-Z y 0-9). Solo valida,
+    /// no se guarda
+    pub allow_any_symbol: bool,
+    
+    /// Habilita drain_reserve() para un wind-down. Sin esto (deploys
+    /// inmutables) el drain no se puede usar nunca
+    pub drain_enabled: bool,
+    
+    /// Registra la última contraparte de cada cuenta en cada transfer
+    /// (last_counterparty). Off por default para evitar las escrituras
+    pub track_counterparties: bool,
+}
+
+/// Firmantes y umbral del multisig configurado en
+/// initialize_with_multisig()
+/// 
+/// Una propuesta se ejecuta al juntar `threshold` aprobaciones de
+/// firmantes distintos (1 <= threshold <= cantidad de firmantes)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Multisig {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Acción de alto impacto que ejecuta el multisig
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    /// Mintea `amount` a la cuenta (mismas reglas que mint)
+    Mint(Address, i128),
+    /// Pausa según la pause_policy vigente
+    Pause,
+    Unpause,
+    /// Fee en bps y treasury (mismas reglas que set_transfer_fee)
+    SetTransferFee(u32, Address),
+    /// Reemplaza el WASM del contrato por el del hash
+    Upgrade(BytesN<32>),
+}
+
+/// Propuesta pendiente devuelta por proposal()
+/// 
+/// `approvals` incluye al firmante que la propuso
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub action: AdminAction,
+    pub approvals: Vec<Address>,
+}
+
+/// Tramo de un vesting creado con mint_vested()
+/// 
+/// `amount` queda bloqueado hasta `unlock_ledger` (inclusive se puede
+/// reclamar a partir de ese ledger)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestTranche {
+    pub amount: i128,
+    pub unlock_ledger: u32,
+}
+
+/// Pago registrado por transfer_from_tracked() para un refund posterior
+/// 
+/// `amount` es lo que recibió `to` (neto de fee), expresado con los
+/// `decimals` vigentes al registrarlo: refund() lo reescala si después
+/// se migraron los decimales
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentRecord {
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub refunded: bool,
+    pub decimals: u32,
+}
+
+/// Dirección de redondeo para el cálculo de fees
+/// 
+/// Floor redondea a favor del usuario, Ceil a favor del protocolo.
+/// En ambos casos fee + neto == amount
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoundingMode {
+    Floor,
+    Ceil,
+}
+
+/// Roles delegables por el admin
+/// 
+/// Minter: puede mintear con mint_by(). Pauser: puede pausar (no despausar)
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Minter,
+    Pauser,
+}
+
+/// Estado de la migración única de decimales
+/// 
+/// Disabled -> Enabled (admin la habilita) -> Done (ya se ejecutó)
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MigrationStatus {
+    Disabled,
+    Enabled,
+    Done,
+}
+
+/// Incidencia del fee de transferencia
+/// 
+/// Recipient: el fee sale del amount (`to` recibe amount - fee).
+/// SenderPaysExtra: el fee se cobra encima (`from` paga amount + fee)
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FeePayer {
+    Recipient,
+    SenderPaysExtra,
+}
+/// Resumen del estado del contrato devuelto por health()
+/// 
+/// Agrupa varios getters en una sola lectura para monitoreo
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthReport {
+    pub initialized: bool,
+    pub paused: bool,
+    pub total_supply: i128,
+    pub admin_set: bool,
+    pub version: u32,
+}
+
+/// Balance de una cuenta devuelto por balance_proof()
+/// 
+/// Balance, ledger y supply leídos juntos en la misma invocación,
+/// para que un relayer de bridge los atestigüe como un único estado
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BalanceProof {
+    pub account: Address,
+    pub balance: i128,
+    pub ledger: u32,
+    pub supply: i128,
+}
+
+/// Sentido de la comparación de una PriceCondition
+/// 
+/// Above exige un precio estrictamente mayor al umbral, Below
+/// estrictamente menor
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PriceDirection {
+    Above,
+    Below,
+}
+
+/// Condición de precio configurada con set_price_condition()
+/// 
+/// Las transferencias solo se permiten mientras `get_price()` del
+/// `oracle` cumple `direction` respecto de `threshold`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceCondition {
+    pub oracle: Address,
+    pub threshold: i128,
+    pub direction: PriceDirection,
+}
+
+/// Permisos efectivos de una cuenta devueltos por permissions()
+/// 
+/// Permite a una UI saber qué acciones de gestión puede usar la cuenta
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Permissions {
+    pub can_mint: bool,
+    pub can_pause: bool,
+    pub can_freeze: bool,
+    pub is_admin: bool,
+}
+
+/// Keys persistentes de una cuenta devueltas por account_storage_info()
+/// 
+/// Sirve para entender por qué una cuenta paga más renta y para
+/// detectar entradas huérfanas
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountStorage {
+    pub has_balance: bool,
+    pub has_locked: bool,
+    pub allowance_count: u32,
+    pub is_frozen: bool,
+}
+
+/// Últimas contrapartes de una cuenta devueltas por last_counterparty()
+/// 
+/// `sent_to`: a quién envió por última vez; `received_from`: de quién
+/// recibió por última vez. None si todavía no hubo transferencia
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LastCounterparty {
+    pub sent_to: Option<Address>,
+    pub received_from: Option<Address>,
+}
+
+/// Transferencia retenida devuelta por pending_transfer()
+/// 
+/// `debit` ya se descontó de `from`; al liquidar, `to` recibe `credit`
+/// y la diferencia (el fee) va a treasury / fee_recipients. Se puede
+/// liquidar desde `settle_ledger` y cancelar antes de ese ledger
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub debit: i128,
+    pub credit: i128,
+    pub settle_ledger: u32,
+}
+
+/// Niveles de TTL de los balances configurados con set_ttl_tiers()
+/// 
+/// Una cuenta que envió tokens en los últimos `active_window` ledgers
+/// extiende su balance a `active_extend_to` ledgers; el resto (por
+/// ejemplo una cuenta que solo recibió) a `baseline_extend_to`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlTiers {
+    pub active_window: u32,
+    pub active_extend_to: u32,
+    pub baseline_extend_to: u32,
+}
+
+/// Resultado de transfer_with_receipt()
+/// 
+/// `fee` es el fee en tokens (0 sin fee configurado). Si la
+/// transferencia quedó pendiente (DelayThreshold) `pending_id` trae el
+/// id para settle_transfer() / cancel_transfer() y `new_to_balance` es
+/// el balance actual de `to`, todavía sin acreditar
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferReceipt {
+    pub amount: i128,
+    pub fee: i128,
+    pub new_from_balance: i128,
+    pub new_to_balance: i128,
+    pub ledger: u32,
+    pub pending_id: Option<u64>,
+}
+
+/// Configuración completa del contrato devuelta por config()
+/// 
+/// Los valores no configurados se reportan con su default
+/// (mismo valor que devuelve el getter individual). Ya tiene los 40
+/// campos que permite el spec: las opciones nuevas solo tienen getter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenConfig {
+    pub admin: Option<Address>,
+    pub community_pool_enabled: bool,
+    pub track_tx_count: bool,
+    pub event_namespace: Option<Symbol>,
+    pub event_seq: bool,
+    pub sender_seq: bool,
+    pub reserve: Option<Address>,
+    pub paused: bool,
+    pub pause_policy: u32,
+    pub guardian: Option<Address>,
+    pub mint_controller: Option<Address>,
+    pub allow_mint_to_frozen: bool,
+    pub max_allowances_per_owner: u32,
+    pub allowance_grace: u32,
+    pub require_spender_opt_in: bool,
+    pub transfer_fee_bps: u32,
+    pub flat_fee: i128,
+    pub treasury: Option<Address>,
+    pub fee_recipients: Vec<(Address, u32)>,
+    pub rounding_mode: RoundingMode,
+    pub fee_payer: FeePayer,
+    pub native_token: Option<Address>,
+    pub xlm_fee: i128,
+    pub xlm_fee_collector: Option<Address>,
+    pub min_account_balance: i128,
+    pub max_holders: u32,
+    pub action_cooldown: u32,
+    pub legacy_token: Option<Address>,
+    pub migration_rate_bps: u32,
+    pub max_supply: Option<i128>,
+    /// Solo el rate (0 = sin inflación); el resto con inflation()
+    pub inflation_rate_bps: u32,
+    pub buyback_executor: Option<Address>,
+    pub notification_relay: Option<Address>,
+    pub default_volume_limit: Option<i128>,
+    pub volume_window: u32,
+    pub launch_guard_until: u32,
+    pub near_expiry_warnings: bool,
+    pub emit_failure_events: bool,
+    pub suppress_noop_events: bool,
+    pub require_non_empty: bool,
+}